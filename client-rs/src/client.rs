@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use extrachat_protocol::{
+    AuthenticateRequest,
+    codec,
+    MAX_VERSION,
+    MIN_VERSION,
+    PingRequest,
+    RequestContainer,
+    RequestKind,
+    ResponseKind,
+    VersionRequest,
+    VersionResponse,
+};
+use futures_util::{SinkExt, StreamExt};
+use log::{debug, warn};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
+use tokio::sync::oneshot;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use crate::{Error, Event, Events, Result};
+
+/// Where to find a server.
+#[derive(Debug, Clone)]
+pub enum Address {
+    Unix(PathBuf),
+    /// A `host:port` pair.
+    Tcp(String),
+}
+
+/// A handle to a connection. Cheap to clone; the connection closes once
+/// every handle has been dropped.
+#[derive(Clone)]
+pub struct Client {
+    shared: Arc<Shared>,
+    outgoing: UnboundedSender<Message>,
+}
+
+struct Shared {
+    next_number: AtomicU32,
+    waiters: Mutex<HashMap<u32, oneshot::Sender<ResponseKind>>>,
+}
+
+impl Client {
+    pub async fn connect(address: &Address) -> Result<(Self, Events)> {
+        match address {
+            Address::Unix(path) => {
+                let stream = UnixStream::connect(path).await?;
+                let (ws, _) = tokio_tungstenite::client_async("ws://localhost/", stream).await?;
+                Ok(Self::from_stream(ws))
+            }
+            Address::Tcp(address) => {
+                let stream = TcpStream::connect(address).await?;
+                let (ws, _) = tokio_tungstenite::client_async(format!("ws://{}/", address), stream).await?;
+                Ok(Self::from_stream(ws))
+            }
+        }
+    }
+
+    /// Takes over an already-established websocket, e.g. one using TLS.
+    /// Must be called from within a tokio runtime.
+    pub fn from_stream<S>(ws: WebSocketStream<S>) -> (Self, Events)
+        where S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let shared = Arc::new(Shared {
+            // 0 is used by the server for pushes
+            next_number: AtomicU32::new(1),
+            waiters: Mutex::new(HashMap::new()),
+        });
+        let (outgoing_tx, outgoing_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (events_tx, events_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        tokio::task::spawn(run(ws, Arc::clone(&shared), outgoing_rx, events_tx));
+
+        let client = Self {
+            shared,
+            outgoing: outgoing_tx,
+        };
+
+        (client, Events { rx: events_rx })
+    }
+
+    fn next_number(&self) -> u32 {
+        loop {
+            let number = self.shared.next_number.fetch_add(1, Ordering::Relaxed);
+            if number != 0 {
+                return number;
+            }
+        }
+    }
+
+    fn send_numbered(&self, number: u32, kind: RequestKind) -> Result<()> {
+        let bytes = codec::encode_request(&RequestContainer {
+            number,
+            kind,
+        })?;
+
+        self.outgoing.send(Message::Binary(bytes))
+            .map_err(|_| Error::Disconnected)
+    }
+
+    /// Sends a request without waiting for a response, returning its
+    /// number. Any response arrives as [`Event::Unsolicited`].
+    pub fn send(&self, kind: impl Into<RequestKind>) -> Result<u32> {
+        let number = self.next_number();
+        self.send_numbered(number, kind.into())?;
+        Ok(number)
+    }
+
+    /// Sends a request and waits for the response with the same number.
+    /// Error responses are returned as [`Error::Server`].
+    ///
+    /// Only use this for requests the server always answers: a message,
+    /// for example, is only answered with a `MessageAck` if the
+    /// `message_ack` capability was negotiated, and is otherwise only
+    /// echoed back as an [`Event::Message`].
+    pub async fn request(&self, kind: impl Into<RequestKind>) -> Result<ResponseKind> {
+        let number = self.next_number();
+        let (tx, rx) = oneshot::channel();
+        self.shared.waiters.lock().unwrap().insert(number, tx);
+
+        if let Err(e) = self.send_numbered(number, kind.into()) {
+            self.shared.waiters.lock().unwrap().remove(&number);
+            return Err(e);
+        }
+
+        match rx.await {
+            Ok(ResponseKind::Error(e)) => Err(e.into()),
+            Ok(kind) => Ok(kind),
+            Err(_) => Err(Error::Disconnected),
+        }
+    }
+
+    pub async fn ping(&self) -> Result<()> {
+        match self.request(PingRequest {}).await? {
+            ResponseKind::Ping(_) => Ok(()),
+            kind => Err(Error::UnexpectedResponse(Box::new(kind))),
+        }
+    }
+
+    /// Negotiates any version this crate supports, plus whichever of
+    /// `capabilities` the server also supports.
+    pub async fn version(&self, capabilities: &[&str]) -> Result<VersionResponse> {
+        let req = VersionRequest {
+            version: MAX_VERSION,
+            min_version: Some(MIN_VERSION),
+            capabilities: capabilities.iter().map(ToString::to_string).collect(),
+        };
+
+        match self.request(req).await? {
+            ResponseKind::Version(resp) => Ok(resp),
+            kind => Err(Error::UnexpectedResponse(Box::new(kind))),
+        }
+    }
+
+    /// Logs in with an account key from registration. `public_key` is this
+    /// client's key exchange public key.
+    pub async fn authenticate(&self, key: impl Into<String>, public_key: Vec<u8>, allow_invites: bool) -> Result<()> {
+        let req = AuthenticateRequest {
+            key: key.into().into(),
+            pk: public_key.into(),
+            allow_invites,
+        };
+
+        match self.request(req).await? {
+            ResponseKind::Authenticate(resp) => match resp.error {
+                None => Ok(()),
+                Some(message) => Err(Error::Server {
+                    channel: None,
+                    code: resp.code.unwrap_or_default(),
+                    message,
+                }),
+            },
+            kind => Err(Error::UnexpectedResponse(Box::new(kind))),
+        }
+    }
+}
+
+async fn run<S>(mut ws: WebSocketStream<S>, shared: Arc<Shared>, mut outgoing: UnboundedReceiver<Message>, events: UnboundedSender<Event>)
+    where S: AsyncRead + AsyncWrite + Unpin,
+{
+    let result: Result<()> = loop {
+        tokio::select! {
+            msg = outgoing.recv() => match msg {
+                Some(msg) => if let Err(e) = ws.send(msg).await {
+                    break Err(e.into());
+                },
+                // every handle was dropped
+                None => {
+                    ws.close(None).await.ok();
+                    break Ok(());
+                }
+            },
+            msg = ws.next() => match msg {
+                Some(Ok(Message::Binary(bytes))) => dispatch(&shared, &events, &bytes),
+                Some(Ok(Message::Close(_))) | None => break Ok(()),
+                // pings are answered by tungstenite
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(e.into()),
+            },
+        }
+    };
+
+    if let Err(e) = result {
+        debug!("connection closed: {}", e);
+    }
+
+    // stop accepting requests before failing the outstanding ones, so
+    // none can be left waiting forever
+    drop(outgoing);
+    shared.waiters.lock().unwrap().clear();
+}
+
+fn dispatch(shared: &Shared, events: &UnboundedSender<Event>, bytes: &[u8]) {
+    let container = match codec::decode_response(bytes) {
+        Ok(container) => container,
+        Err(e) => {
+            warn!("could not decode response: {:#?}", e);
+            return;
+        }
+    };
+
+    if container.number != 0 {
+        let waiter = shared.waiters.lock().unwrap().remove(&container.number);
+        if let Some(waiter) = waiter {
+            // the request may have been cancelled
+            waiter.send(container.kind).ok();
+            return;
+        }
+    }
+
+    events.send(Event::from(container)).ok();
+}
@@ -0,0 +1,184 @@
+//! The end-to-end encryption between members of a linkshell, byte-compatible
+//! with the C# plugin.
+//!
+//! Names and messages are sealed with libsodium's
+//! `crypto_secretbox_xchacha20poly1305` under the channel's shared secret,
+//! with the random nonce prepended: `nonce (24) || mac (16) || ciphertext`.
+//!
+//! Shared secrets are passed between members under session keys from
+//! libsodium's `crypto_kx`. Whoever starts the exchange (the inviter, or the
+//! member asking for a secret) takes the client role.
+
+use blake2::{Blake2b512, Digest};
+use crypto_secretbox::{AeadCore, KeyInit, Nonce, XChaCha20Poly1305};
+use crypto_secretbox::aead::{Aead, OsRng};
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::Zeroize;
+
+pub const KEY_LEN: usize = 32;
+pub const PUBLIC_KEY_LEN: usize = 32;
+pub const NONCE_LEN: usize = 24;
+pub const MAC_LEN: usize = 16;
+
+#[derive(Debug, thiserror::Error)]
+pub enum CryptoError {
+    #[error("key must be {expected} bytes, got {actual}")]
+    InvalidKeyLength {
+        expected: usize,
+        actual: usize,
+    },
+    #[error("ciphertext is too short")]
+    TooShort,
+    #[error("could not decrypt")]
+    Decrypt,
+    #[error("public key is invalid")]
+    InvalidPublicKey,
+}
+
+/// Generates a new channel shared secret.
+pub fn generate_shared_secret() -> Vec<u8> {
+    XChaCha20Poly1305::generate_key(&mut OsRng).to_vec()
+}
+
+/// Seals `plaintext` under `key` with a fresh random nonce.
+pub fn encrypt(key: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    let cipher = secretbox(key)?;
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, plaintext)
+        .expect("secretbox only fails with associated data");
+
+    let mut output = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+    output.extend_from_slice(&nonce);
+    output.extend_from_slice(&ciphertext);
+    Ok(output)
+}
+
+/// Opens something sealed by [`encrypt`] or the C# `SecretBox.Encrypt`.
+pub fn decrypt(key: &[u8], ciphertext: &[u8]) -> Result<Vec<u8>, CryptoError> {
+    if ciphertext.len() < NONCE_LEN + MAC_LEN {
+        return Err(CryptoError::TooShort);
+    }
+
+    let cipher = secretbox(key)?;
+    let (nonce, ciphertext) = ciphertext.split_at(NONCE_LEN);
+    let nonce = <[u8; NONCE_LEN]>::try_from(nonce).map_err(|_| CryptoError::TooShort)?;
+    cipher.decrypt(&Nonce::from(nonce), ciphertext)
+        .map_err(|_| CryptoError::Decrypt)
+}
+
+fn secretbox(key: &[u8]) -> Result<XChaCha20Poly1305, CryptoError> {
+    XChaCha20Poly1305::new_from_slice(key)
+        .map_err(|_| CryptoError::InvalidKeyLength {
+            expected: KEY_LEN,
+            actual: key.len(),
+        })
+}
+
+/// A `crypto_kx` key pair. The public key is what gets sent in
+/// `AuthenticateRequest.pk`.
+pub struct KeyPair {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+/// Session keys from one side of a key exchange. The other side's `rx` is
+/// this side's `tx` and vice versa.
+pub struct SessionKeys {
+    pub rx: [u8; KEY_LEN],
+    pub tx: [u8; KEY_LEN],
+}
+
+impl Drop for SessionKeys {
+    fn drop(&mut self) {
+        self.rx.zeroize();
+        self.tx.zeroize();
+    }
+}
+
+impl KeyPair {
+    pub fn generate() -> Self {
+        Self::from_secret_key(StaticSecret::random().to_bytes())
+    }
+
+    pub fn from_secret_key(secret_key: [u8; KEY_LEN]) -> Self {
+        let secret = StaticSecret::from(secret_key);
+        let public = PublicKey::from(&secret);
+        Self {
+            secret,
+            public,
+        }
+    }
+
+    pub fn secret_key(&self) -> [u8; KEY_LEN] {
+        self.secret.to_bytes()
+    }
+
+    pub fn public_key(&self) -> [u8; PUBLIC_KEY_LEN] {
+        self.public.to_bytes()
+    }
+
+    /// `crypto_kx_client_session_keys`
+    pub fn client_session_keys(&self, server_pk: &[u8]) -> Result<SessionKeys, CryptoError> {
+        let server_pk = public_key(server_pk)?;
+        let [rx, tx] = self.session_keys(&server_pk, &self.public, &server_pk)?;
+        Ok(SessionKeys { rx, tx })
+    }
+
+    /// `crypto_kx_server_session_keys`
+    pub fn server_session_keys(&self, client_pk: &[u8]) -> Result<SessionKeys, CryptoError> {
+        let client_pk = public_key(client_pk)?;
+        let [tx, rx] = self.session_keys(&client_pk, &client_pk, &self.public)?;
+        Ok(SessionKeys { rx, tx })
+    }
+
+    fn session_keys(&self, their_pk: &PublicKey, client_pk: &PublicKey, server_pk: &PublicKey) -> Result<[[u8; KEY_LEN]; 2], CryptoError> {
+        let shared = self.secret.diffie_hellman(their_pk);
+        if !shared.was_contributory() {
+            return Err(CryptoError::InvalidPublicKey);
+        }
+
+        let mut hash = Blake2b512::new()
+            .chain_update(shared.as_bytes())
+            .chain_update(client_pk.as_bytes())
+            .chain_update(server_pk.as_bytes())
+            .finalize();
+
+        let mut keys = [[0; KEY_LEN]; 2];
+        keys[0].copy_from_slice(&hash[..KEY_LEN]);
+        keys[1].copy_from_slice(&hash[KEY_LEN..]);
+        hash.zeroize();
+
+        Ok(keys)
+    }
+
+    /// Encrypts a channel's shared secret for `InviteRequest.encrypted_secret`.
+    pub fn encrypt_invite(&self, invitee_pk: &[u8], shared_secret: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        encrypt(&self.client_session_keys(invitee_pk)?.tx, shared_secret)
+    }
+
+    /// Decrypts the shared secret in an `InvitedResponse`.
+    pub fn decrypt_invite(&self, inviter_pk: &[u8], encrypted_secret: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        decrypt(&self.server_session_keys(inviter_pk)?.rx, encrypted_secret)
+    }
+
+    /// Encrypts a channel's shared secret for
+    /// `SendSecretsRequest.encrypted_shared_secret`, in reply to a
+    /// `SendSecretsResponse` from `requester_pk`.
+    pub fn encrypt_requested_secret(&self, requester_pk: &[u8], shared_secret: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        encrypt(&self.server_session_keys(requester_pk)?.tx, shared_secret)
+    }
+
+    /// Decrypts the shared secret in a `SecretsResponse`.
+    pub fn decrypt_requested_secret(&self, sender_pk: &[u8], encrypted_secret: &[u8]) -> Result<Vec<u8>, CryptoError> {
+        decrypt(&self.client_session_keys(sender_pk)?.rx, encrypted_secret)
+    }
+}
+
+fn public_key(bytes: &[u8]) -> Result<PublicKey, CryptoError> {
+    let bytes: [u8; PUBLIC_KEY_LEN] = bytes.try_into()
+        .map_err(|_| CryptoError::InvalidKeyLength {
+            expected: PUBLIC_KEY_LEN,
+            actual: bytes.len(),
+        })?;
+    Ok(PublicKey::from(bytes))
+}
@@ -0,0 +1,45 @@
+use extrachat_protocol::{ErrorCode, ErrorResponse, ResponseKind};
+use extrachat_protocol::codec::EncodeError;
+use tokio_tungstenite::tungstenite::Error as WsError;
+use uuid::Uuid;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("could not connect: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("websocket error: {0}")]
+    WebSocket(Box<WsError>),
+    #[error("could not encode request: {0}")]
+    Encode(#[from] EncodeError),
+    /// The connection closed before a response arrived.
+    #[error("disconnected")]
+    Disconnected,
+    /// The server replied with an error.
+    #[error("server error ({code:?}): {message}")]
+    Server {
+        channel: Option<Uuid>,
+        code: ErrorCode,
+        message: String,
+    },
+    /// The server replied with a response of the wrong kind for the request.
+    #[error("unexpected response: {0:?}")]
+    UnexpectedResponse(Box<ResponseKind>),
+}
+
+impl From<WsError> for Error {
+    fn from(e: WsError) -> Self {
+        Self::WebSocket(Box::new(e))
+    }
+}
+
+impl From<ErrorResponse> for Error {
+    fn from(e: ErrorResponse) -> Self {
+        Self::Server {
+            channel: e.channel,
+            code: e.code,
+            message: e.error,
+        }
+    }
+}
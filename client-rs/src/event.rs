@@ -0,0 +1,90 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use extrachat_protocol::{
+    AnnounceResponse,
+    DirectMessageReceivedResponse,
+    DisbandResponse,
+    InvitedResponse,
+    MemberChangeResponse,
+    MessageDeletedResponse,
+    MessageEditedResponse,
+    MessageResponse,
+    ReactionChangedResponse,
+    ResponseContainer,
+    ResponseKind,
+    SendSecretsResponse,
+    TypingResponse,
+    UpdatedResponse,
+};
+use futures_util::Stream;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Something the server sent without being asked.
+#[derive(Debug, Clone)]
+pub enum Event {
+    Message(MessageResponse),
+    /// Every change to a channel's members, including expired invites,
+    /// bans and mutes. Check `kind` to tell them apart.
+    MemberChange(MemberChangeResponse),
+    Invited(InvitedResponse),
+    /// Another member wants a channel's shared secret. Reply with a
+    /// `SendSecretsRequest`.
+    SendSecrets(SendSecretsResponse),
+    Announce(AnnounceResponse),
+    Updated(UpdatedResponse),
+    Disband(DisbandResponse),
+    MessageEdited(MessageEditedResponse),
+    MessageDeleted(MessageDeletedResponse),
+    ReactionChanged(ReactionChangedResponse),
+    Typing(TypingResponse),
+    DirectMessageReceived(DirectMessageReceivedResponse),
+    /// A response that no request was waiting for, such as an error for a
+    /// request made with [`Client::send`](crate::Client::send).
+    Unsolicited(ResponseContainer),
+}
+
+impl From<ResponseContainer> for Event {
+    fn from(container: ResponseContainer) -> Self {
+        match container.kind {
+            ResponseKind::Message(resp) => Self::Message(resp),
+            ResponseKind::MemberChange(resp) => Self::MemberChange(resp),
+            ResponseKind::Invited(resp) => Self::Invited(resp),
+            ResponseKind::SendSecrets(resp) => Self::SendSecrets(resp),
+            ResponseKind::Announce(resp) => Self::Announce(resp),
+            ResponseKind::Updated(resp) => Self::Updated(resp),
+            ResponseKind::Disband(resp) => Self::Disband(resp),
+            ResponseKind::MessageEdited(resp) => Self::MessageEdited(resp),
+            ResponseKind::MessageDeleted(resp) => Self::MessageDeleted(resp),
+            ResponseKind::ReactionChanged(resp) => Self::ReactionChanged(resp),
+            ResponseKind::Typing(resp) => Self::Typing(resp),
+            ResponseKind::DirectMessageReceived(resp) => Self::DirectMessageReceived(resp),
+            kind => Self::Unsolicited(ResponseContainer {
+                number: container.number,
+                kind,
+            }),
+        }
+    }
+}
+
+/// The events for one connection. Ends when the connection closes.
+///
+/// Events are buffered without limit, so keep draining this even if you
+/// only care about responses.
+pub struct Events {
+    pub(crate) rx: UnboundedReceiver<Event>,
+}
+
+impl Events {
+    pub async fn recv(&mut self) -> Option<Event> {
+        self.rx.recv().await
+    }
+}
+
+impl Stream for Events {
+    type Item = Event;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.rx.poll_recv(cx)
+    }
+}
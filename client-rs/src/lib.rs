@@ -0,0 +1,32 @@
+//! An async client for the ExtraChat protocol, for tooling, bots and load
+//! tests.
+//!
+//! ```no_run
+//! # async fn run() -> extrachat_client::Result<()> {
+//! use extrachat_client::{Address, Client, Event};
+//!
+//! let (client, mut events) = Client::connect(&Address::Tcp("localhost:8080".into())).await?;
+//! client.version(&[]).await?;
+//! client.authenticate("key", vec![0; 32], true).await?;
+//!
+//! while let Some(event) = events.recv().await {
+//!     if let Event::Announce(announce) = event {
+//!         println!("{}", announce.announcement);
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+pub use extrachat_protocol as protocol;
+
+pub use self::{
+    client::*,
+    error::*,
+    event::*,
+};
+
+pub mod client;
+pub mod crypto;
+pub mod error;
+pub mod event;
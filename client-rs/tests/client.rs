@@ -0,0 +1,105 @@
+use extrachat_client::{Address, Client, Error, Event};
+use extrachat_client::protocol::{
+    AnnounceResponse,
+    AuthenticateResponse,
+    codec,
+    ErrorCode,
+    ErrorResponse,
+    PingResponse,
+    RequestKind,
+    ResponseContainer,
+    ResponseKind,
+    VersionResponse,
+};
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Accepts one connection and answers requests like a server would,
+/// except that pings are answered in reverse order of arrival.
+async fn mock_server() -> Address {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+
+    tokio::task::spawn(async move {
+        let (sock, _) = listener.accept().await.unwrap();
+        let mut ws = tokio_tungstenite::accept_async(sock).await.unwrap();
+        let mut pings = Vec::new();
+
+        while let Some(Ok(msg)) = ws.next().await {
+            let Message::Binary(bytes) = msg else {
+                continue;
+            };
+
+            let req = codec::decode_request(&bytes).unwrap();
+            let mut replies = Vec::new();
+            match req.kind {
+                RequestKind::Version(version) => replies.push((req.number, ResponseKind::Version(VersionResponse {
+                    version: version.version,
+                    capabilities: Vec::new(),
+                }))),
+                RequestKind::Authenticate(auth) if auth.key.as_str() == "good" => {
+                    replies.push((req.number, ResponseKind::Authenticate(AuthenticateResponse::success())));
+                    replies.push((0, ResponseKind::Announce(AnnounceResponse::new("welcome"))));
+                }
+                RequestKind::Authenticate(_) => replies.push((req.number, ResponseKind::Authenticate(AuthenticateResponse::error(ErrorCode::InvalidKey, "invalid key")))),
+                RequestKind::Ping(_) => {
+                    pings.push(req.number);
+                    if pings.len() == 2 {
+                        replies.extend(pings.drain(..).rev().map(|number| (number, ResponseKind::Ping(PingResponse {}))));
+                    }
+                }
+                _ => replies.push((req.number, ResponseKind::Error(ErrorResponse::new(None, ErrorCode::NotImplemented, "nope")))),
+            }
+
+            for (number, kind) in replies {
+                let bytes = codec::encode_response(&ResponseContainer { number, kind }).unwrap();
+                ws.send(Message::Binary(bytes)).await.unwrap();
+            }
+        }
+    });
+
+    Address::Tcp(address.to_string())
+}
+
+#[tokio::test]
+async fn handshake_and_events() {
+    let (client, mut events) = Client::connect(&mock_server().await).await.unwrap();
+
+    let version = client.version(&[]).await.unwrap();
+    assert_eq!(version.version, extrachat_client::protocol::MAX_VERSION);
+
+    let err = client.authenticate("bad", vec![0; 32], true).await.unwrap_err();
+    assert!(matches!(err, Error::Server { code: ErrorCode::InvalidKey, .. }));
+
+    client.authenticate("good", vec![0; 32], true).await.unwrap();
+    match events.recv().await {
+        Some(Event::Announce(announce)) => assert_eq!(announce.announcement, "welcome"),
+        event => panic!("unexpected event {event:?}"),
+    }
+}
+
+#[tokio::test]
+async fn responses_are_correlated() {
+    let (client, _events) = Client::connect(&mock_server().await).await.unwrap();
+
+    // the server holds the first ping until the second arrives, then
+    // answers the second first
+    let (first, second) = tokio::join!(client.ping(), client.ping());
+    first.unwrap();
+    second.unwrap();
+}
+
+#[tokio::test]
+async fn unanswered_requests_are_events() {
+    let (client, mut events) = Client::connect(&mock_server().await).await.unwrap();
+
+    let number = client.send(extrachat_client::protocol::ListRequest::All).unwrap();
+    match events.recv().await {
+        Some(Event::Unsolicited(container)) => {
+            assert_eq!(container.number, number);
+            assert!(matches!(container.kind, ResponseKind::Error(_)));
+        }
+        event => panic!("unexpected event {event:?}"),
+    }
+}
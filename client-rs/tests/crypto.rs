@@ -0,0 +1,100 @@
+//! Vectors produced by libsodium, which the C# plugin uses through ASodium.
+//! The secret keys are 00..1f for the client and 20..3f for the server, the
+//! channel shared secret is 40..5f and every nonce is 60..77.
+
+use extrachat_client::crypto::{self, CryptoError, KeyPair};
+
+const CLIENT_PK: &str = "8f40c5adb68f25624ae5b214ea767a6ec94d829d3d7b5e1ad1ba6f3e2138285f";
+const SERVER_PK: &str = "358072d6365880d1aeea329adf9121383851ed21a28e3b75e965d0d2cd166254";
+const CLIENT_RX: &str = "09fcadb630f490a255a9461619a3a32586c5ec11be9a584832de0aad99099e02";
+const CLIENT_TX: &str = "a1c994d365e824f318de66626a225b70a6f3be5b0febe8638882fd8a20d0bf0c";
+/// `SecretBox.Encrypt(shared, "hello linkshell")`
+const MESSAGE: &str = "606162636465666768696a6b6c6d6e6f7071727374757677d2cd6510d524e5c6b6aede6fe0ea810419161a3721e8ac7f36989b877b310b";
+/// `SecretBox.Encrypt(shared, [])`
+const EMPTY: &str = "606162636465666768696a6b6c6d6e6f70717273747576779f98f64fa2142f914421fbff4d7ccd48";
+/// `SecretBox.Encrypt(kx.TransferSharedSecret, shared)` from the client
+const INVITE_SECRET: &str = "606162636465666768696a6b6c6d6e6f70717273747576775cb9366ba1e7b43df0dcd6164e99a6e8eb5d7fb0486f250a70cab1725acc877bff64c504d0597b9ef7c1377fb997891e";
+
+fn hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+fn key(start: u8) -> [u8; 32] {
+    std::array::from_fn(|i| start + i as u8)
+}
+
+fn client() -> KeyPair {
+    KeyPair::from_secret_key(key(0x00))
+}
+
+fn server() -> KeyPair {
+    KeyPair::from_secret_key(key(0x20))
+}
+
+#[test]
+fn public_keys() {
+    assert_eq!(client().public_key().to_vec(), hex(CLIENT_PK));
+    assert_eq!(server().public_key().to_vec(), hex(SERVER_PK));
+}
+
+#[test]
+fn session_keys() {
+    let client_keys = client().client_session_keys(&hex(SERVER_PK)).unwrap();
+    assert_eq!(client_keys.rx.to_vec(), hex(CLIENT_RX));
+    assert_eq!(client_keys.tx.to_vec(), hex(CLIENT_TX));
+
+    let server_keys = server().server_session_keys(&hex(CLIENT_PK)).unwrap();
+    assert_eq!(server_keys.rx, client_keys.tx);
+    assert_eq!(server_keys.tx, client_keys.rx);
+}
+
+#[test]
+fn decrypt_secretbox() {
+    let shared = key(0x40);
+    assert_eq!(crypto::decrypt(&shared, &hex(MESSAGE)).unwrap(), b"hello linkshell");
+    assert_eq!(crypto::decrypt(&shared, &hex(EMPTY)).unwrap(), b"");
+
+    let mut tampered = hex(MESSAGE);
+    *tampered.last_mut().unwrap() ^= 1;
+    assert!(matches!(crypto::decrypt(&shared, &tampered), Err(CryptoError::Decrypt)));
+    assert!(matches!(crypto::decrypt(&shared, &hex(MESSAGE)[..39]), Err(CryptoError::TooShort)));
+}
+
+#[test]
+fn encrypt_secretbox() {
+    let shared = crypto::generate_shared_secret();
+    let sealed = crypto::encrypt(&shared, b"hello linkshell").unwrap();
+    assert_eq!(sealed.len(), hex(MESSAGE).len());
+    assert_eq!(crypto::decrypt(&shared, &sealed).unwrap(), b"hello linkshell");
+
+    assert!(matches!(
+        crypto::encrypt(&shared[..16], b""),
+        Err(CryptoError::InvalidKeyLength { expected: 32, actual: 16 }),
+    ));
+}
+
+#[test]
+fn invite_secret() {
+    // the invitee opens what the C# inviter sealed
+    let secret = server().decrypt_invite(&hex(CLIENT_PK), &hex(INVITE_SECRET)).unwrap();
+    assert_eq!(secret, key(0x40));
+
+    let sealed = client().encrypt_invite(&hex(SERVER_PK), &secret).unwrap();
+    assert_eq!(server().decrypt_invite(&hex(CLIENT_PK), &sealed).unwrap(), secret);
+}
+
+#[test]
+fn requested_secret() {
+    // the server role here is the member answering a `SendSecretsResponse`
+    let secret = key(0x40);
+    let sealed = server().encrypt_requested_secret(&hex(CLIENT_PK), &secret).unwrap();
+    assert_eq!(client().decrypt_requested_secret(&hex(SERVER_PK), &sealed).unwrap(), secret);
+}
+
+#[test]
+fn low_order_public_key() {
+    assert!(matches!(client().client_session_keys(&[0; 32]), Err(CryptoError::InvalidPublicKey)));
+}
@@ -18,6 +18,12 @@ public class Channel {
     [Key(2)]
     public List<Member> Members;
 
+    [Key(3)]
+    public ulong LastRead;
+
+    [Key(4)]
+    public ulong Unread;
+
     internal string DecryptName(byte[] key) {
         return Encoding.UTF8.GetString(SecretBox.Decrypt(key, this.Name));
     }
@@ -15,4 +15,10 @@ public class SimpleChannel {
 
     [Key(2)]
     public Rank Rank;
+
+    [Key(3)]
+    public ulong LastRead;
+
+    [Key(4)]
+    public ulong Unread;
 }
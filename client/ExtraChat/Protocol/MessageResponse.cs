@@ -18,4 +18,16 @@ public class MessageResponse {
     
     [Key(3)]
     public byte[] Message;
+
+    [Key(4)]
+    public ulong Id;
+
+    [Key(5)]
+    public long Timestamp;
+
+    [Key(6)]
+    public ulong Sequence;
+
+    [Key(7)]
+    public ulong? ReplyTo;
 }
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-use crate::util::redacted::Redacted;
+use crate::ErrorCode;
+use crate::redacted::Redacted;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthenticateRequest {
     pub key: Redacted<String>,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub pk: Redacted<Vec<u8>>,
     #[serde(default = "default_true")]
     pub allow_invites: bool,
@@ -14,18 +15,22 @@ pub struct AuthenticateRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthenticateResponse {
     pub error: Option<String>,
+    #[serde(default)]
+    pub code: Option<ErrorCode>,
 }
 
 impl AuthenticateResponse {
     pub fn success() -> Self {
         Self {
             error: None,
+            code: None,
         }
     }
 
-    pub fn error(error: impl Into<String>) -> Self {
+    pub fn error(code: ErrorCode, error: impl Into<String>) -> Self {
         Self {
             error: Some(error.into()),
+            code: Some(code),
         }
     }
 }
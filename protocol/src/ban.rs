@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::redacted::Redacted;
+
+/// Removes a character from a channel, or cancels their invite, and stops
+/// them being invited again until they're unbanned or the ban expires.
+/// Characters who aren't in the channel can be banned too. Moderators and
+/// up only. Requires the [`BANS`](crate::BANS) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanRequest {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    /// Encrypted with the channel's shared secret, like messages.
+    #[serde(with = "crate::bytes")]
+    pub reason: Option<Redacted<Vec<u8>>>,
+    /// Seconds until the ban is lifted, or `None` to ban until unbanned.
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnbanRequest {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnbanResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelBan {
+    pub name: String,
+    pub world: u16,
+    pub banner: String,
+    pub banner_world: u16,
+    #[serde(with = "crate::bytes")]
+    pub reason: Option<Redacted<Vec<u8>>>,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: i64,
+    /// Milliseconds since the Unix epoch, if the ban expires.
+    pub expires_at: Option<i64>,
+}
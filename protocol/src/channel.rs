@@ -0,0 +1,90 @@
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Channel {
+    pub id: Uuid,
+    #[serde(with = "crate::bytes")]
+    pub name: Vec<u8>,
+    pub members: Vec<ChannelMember>,
+    /// See [`SimpleChannel::last_read`]. Only filled in when listing your
+    /// channels.
+    #[serde(default)]
+    pub last_read: u64,
+    /// See [`SimpleChannel::unread`]. Only filled in when listing your
+    /// channels.
+    #[serde(default)]
+    pub unread: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelMember {
+    pub name: String,
+    pub world: u16,
+    pub rank: Rank,
+    pub online: bool,
+    /// When the member's mute is lifted, in milliseconds since the Unix
+    /// epoch, if they're muted.
+    #[serde(default)]
+    pub muted_until: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize_repr, Deserialize_repr, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "snake_case")]
+#[repr(u8)]
+pub enum Rank {
+    Invited = 0,
+    Member = 1,
+    Moderator = 2,
+    Admin = 3,
+}
+
+impl Rank {
+    pub fn from_u8(u: u8) -> Self {
+        match u {
+            0 => Self::Invited,
+            1 => Self::Member,
+            2 => Self::Moderator,
+            3 => Self::Admin,
+            _ => Rank::Member,
+        }
+    }
+
+    pub fn as_u8(self) -> u8 {
+        match self {
+            Self::Invited => 0,
+            Self::Member => 1,
+            Self::Moderator => 2,
+            Self::Admin => 3,
+        }
+    }
+}
+
+impl From<u8> for Rank {
+    fn from(u: u8) -> Self {
+        Rank::from_u8(u)
+    }
+}
+
+impl From<Rank> for u8 {
+    fn from(r: Rank) -> Self {
+        r.as_u8()
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimpleChannel {
+    pub id: Uuid,
+    #[serde(with = "crate::bytes")]
+    pub name: Vec<u8>,
+    pub rank: Rank,
+    /// The sequence number of the last message you've read. Always zero
+    /// for channels you're only invited to.
+    #[serde(default)]
+    pub last_read: u64,
+    /// How many messages have been sent since then. Always zero for
+    /// channels you're only invited to.
+    #[serde(default)]
+    pub unread: u64,
+}
@@ -0,0 +1,140 @@
+//! The encodings used on the wire.
+//!
+//! Binary frames are msgpack. Structs are encoded as arrays of their fields
+//! in declaration order and enums as a single-entry map from the snake_case
+//! variant name to its data (or just the name, for unit variants), which is
+//! what the C# `RequestKindFormatter` and `ResponseKindFormatter` read and
+//! write.
+//!
+//! Text frames are JSON with the same shape, except that structs are objects
+//! keyed by field name, byte fields are base64 strings and UUIDs are
+//! hyphenated strings:
+//!
+//! ```json
+//! {"number": 1, "kind": {"message": {"channel": "…", "message": "AQID"}}}
+//! ```
+
+use std::fmt::{Display, Formatter};
+
+use serde::Deserialize;
+
+use crate::{RequestContainer, ResponseContainer};
+use crate::lenient::Lenient;
+
+/// Which kind of websocket frame a message travels in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Encoding {
+    /// msgpack in binary frames
+    #[default]
+    MessagePack,
+    /// JSON in text frames
+    Json,
+}
+
+#[derive(Debug)]
+pub enum EncodeError {
+    MessagePack(rmp_serde::encode::Error),
+    Json(serde_json::Error),
+}
+
+#[derive(Debug)]
+pub enum DecodeError {
+    MessagePack(rmp_serde::decode::Error),
+    Json(serde_json::Error),
+}
+
+impl Display for EncodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MessagePack(e) => Display::fmt(e, f),
+            Self::Json(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MessagePack(e) => Display::fmt(e, f),
+            Self::Json(e) => Display::fmt(e, f),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl std::error::Error for DecodeError {}
+
+pub fn encode_request(container: &RequestContainer) -> Result<Vec<u8>, EncodeError> {
+    rmp_serde::to_vec(container).map_err(EncodeError::MessagePack)
+}
+
+pub fn encode_response(container: &ResponseContainer) -> Result<Vec<u8>, EncodeError> {
+    rmp_serde::to_vec(container).map_err(EncodeError::MessagePack)
+}
+
+pub fn encode_request_json(container: &RequestContainer) -> Result<String, EncodeError> {
+    serde_json::to_string(container).map_err(EncodeError::Json)
+}
+
+pub fn encode_response_json(container: &ResponseContainer) -> Result<String, EncodeError> {
+    serde_json::to_string(container).map_err(EncodeError::Json)
+}
+
+/// Decodes a response frame, ignoring any trailing fields this version
+/// doesn't know about.
+pub fn decode_response(bytes: &[u8]) -> Result<ResponseContainer, DecodeError> {
+    let mut de = rmp_serde::Deserializer::new(bytes);
+    ResponseContainer::deserialize(Lenient(&mut de)).map_err(DecodeError::MessagePack)
+}
+
+/// Decodes a JSON response, ignoring any fields this version doesn't know
+/// about.
+pub fn decode_response_json(text: &str) -> Result<ResponseContainer, DecodeError> {
+    serde_json::from_str(text).map_err(DecodeError::Json)
+}
+
+#[derive(Debug)]
+pub struct DecodeFailure {
+    /// The request number, if it could be recovered from the frame.
+    pub number: Option<u32>,
+    pub error: DecodeError,
+}
+
+#[derive(Deserialize)]
+struct RequestNumber {
+    number: u32,
+}
+
+/// Decodes a request frame, ignoring any trailing fields this version
+/// doesn't know about.
+pub fn decode_request(bytes: &[u8]) -> Result<RequestContainer, DecodeFailure> {
+    let mut de = rmp_serde::Deserializer::new(bytes);
+    let error = match RequestContainer::deserialize(Lenient(&mut de)) {
+        Ok(container) => return Ok(container),
+        Err(e) => e,
+    };
+
+    // the kind may be from a newer client, so try to get just the number
+    // to reply to
+    let mut de = rmp_serde::Deserializer::new(bytes);
+    let number = RequestNumber::deserialize(Lenient(&mut de))
+        .ok()
+        .map(|n| n.number);
+
+    Err(DecodeFailure {
+        number,
+        error: DecodeError::MessagePack(error),
+    })
+}
+
+/// Decodes a JSON request, ignoring any fields this version doesn't know
+/// about.
+pub fn decode_request_json(text: &str) -> Result<RequestContainer, DecodeFailure> {
+    serde_json::from_str(text).map_err(|error| DecodeFailure {
+        number: serde_json::from_str::<RequestNumber>(text)
+            .ok()
+            .map(|n| n.number),
+        error: DecodeError::Json(error),
+    })
+}
@@ -0,0 +1,279 @@
+use serde::{Deserialize, Serialize};
+
+use crate::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestContainer {
+    pub number: u32,
+    pub kind: RequestKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    Ping(PingRequest),
+    Version(VersionRequest),
+    Register(RegisterRequest),
+    Authenticate(AuthenticateRequest),
+    Message(MessageRequest),
+    Create(CreateRequest),
+    Disband(DisbandRequest),
+    Invite(InviteRequest),
+    Join(JoinRequest),
+    Leave(LeaveRequest),
+    Kick(KickRequest),
+    List(ListRequest),
+    Promote(PromoteRequest),
+    Update(UpdateRequest),
+    PublicKey(PublicKeyRequest),
+    Secrets(SecretsRequest),
+    SendSecrets(SendSecretsRequest),
+    AllowInvites(AllowInvitesRequest),
+    DeleteAccount(DeleteAccountRequest),
+    History(HistoryRequest),
+    EditMessage(EditMessageRequest),
+    DeleteMessage(DeleteMessageRequest),
+    React(ReactRequest),
+    Typing(TypingRequest),
+    MarkRead(MarkReadRequest),
+    DirectMessage(DirectMessageRequest),
+    AllowDirectMessages(AllowDirectMessagesRequest),
+    InviteKey(InviteKeyRequest),
+    Ban(BanRequest),
+    Unban(UnbanRequest),
+    Mute(MuteRequest),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResponseContainer {
+    pub number: u32,
+    pub kind: ResponseKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ResponseKind {
+    Ping(PingResponse),
+    Version(VersionResponse),
+    Register(RegisterResponse),
+    Authenticate(AuthenticateResponse),
+    Message(MessageResponse),
+    Error(ErrorResponse),
+    Create(CreateResponse),
+    Disband(DisbandResponse),
+    Invite(InviteResponse),
+    Invited(InvitedResponse),
+    Join(JoinResponse),
+    Leave(LeaveResponse),
+    Kick(KickResponse),
+    List(ListResponse),
+    Promote(PromoteResponse),
+    Update(UpdateResponse),
+    Updated(UpdatedResponse),
+    PublicKey(PublicKeyResponse),
+    MemberChange(MemberChangeResponse),
+    Secrets(SecretsResponse),
+    SendSecrets(SendSecretsResponse),
+    Announce(AnnounceResponse),
+    AllowInvites(AllowInvitesResponse),
+    DeleteAccount(DeleteAccountResponse),
+    History(HistoryResponse),
+    MessageAck(MessageAckResponse),
+    EditMessage(EditMessageResponse),
+    MessageEdited(MessageEditedResponse),
+    DeleteMessage(DeleteMessageResponse),
+    MessageDeleted(MessageDeletedResponse),
+    React(ReactResponse),
+    ReactionChanged(ReactionChangedResponse),
+    Typing(TypingResponse),
+    MarkRead(MarkReadResponse),
+    DirectMessage(DirectMessageResponse),
+    DirectMessageReceived(DirectMessageReceivedResponse),
+    AllowDirectMessages(AllowDirectMessagesResponse),
+    InviteKey(InviteKeyResponse),
+    Ban(BanResponse),
+    Unban(UnbanResponse),
+    Mute(MuteResponse),
+}
+
+impl RequestKind {
+    /// What a connection must have negotiated to send this request.
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::Ping(_)
+            | Self::Version(_)
+            | Self::Register(_)
+            | Self::Authenticate(_)
+            | Self::Message(_)
+            | Self::Create(_)
+            | Self::Disband(_)
+            | Self::Invite(_)
+            | Self::Join(_)
+            | Self::Leave(_)
+            | Self::Kick(_)
+            | Self::Promote(_)
+            | Self::PublicKey(_)
+            | Self::Secrets(_)
+            | Self::SendSecrets(_)
+            | Self::AllowInvites(_)
+            | Self::DeleteAccount(_) => Requirement::Version(1),
+            Self::List(list) => list.requirement(),
+            Self::Update(update) => update.kind.requirement(),
+            Self::History(_) => Requirement::Capability(HISTORY),
+            Self::EditMessage(_)
+            | Self::DeleteMessage(_) => Requirement::Capability(MESSAGE_EDIT),
+            Self::React(_) => Requirement::Capability(REACTIONS),
+            Self::Typing(_) => Requirement::Capability(TYPING),
+            Self::MarkRead(_) => Requirement::Capability(READ_MARKERS),
+            Self::DirectMessage(_)
+            | Self::AllowDirectMessages(_) => Requirement::Capability(DIRECT_MESSAGES),
+            Self::InviteKey(_) => Requirement::Capability(OFFLINE_INVITES),
+            Self::Ban(_)
+            | Self::Unban(_) => Requirement::Capability(BANS),
+            Self::Mute(_) => Requirement::Capability(MUTES),
+        }
+    }
+}
+
+macro_rules! request_container {
+    ($name:ident, $request:ty) => {
+        impl From<$request> for RequestKind {
+            fn from(request: $request) -> Self {
+                RequestKind::$name(request)
+            }
+        }
+    };
+}
+
+request_container!(Ping, PingRequest);
+request_container!(Version, VersionRequest);
+request_container!(Register, RegisterRequest);
+request_container!(Authenticate, AuthenticateRequest);
+request_container!(Message, MessageRequest);
+request_container!(Create, CreateRequest);
+request_container!(Disband, DisbandRequest);
+request_container!(Invite, InviteRequest);
+request_container!(Join, JoinRequest);
+request_container!(Leave, LeaveRequest);
+request_container!(Kick, KickRequest);
+request_container!(List, ListRequest);
+request_container!(Promote, PromoteRequest);
+request_container!(Update, UpdateRequest);
+request_container!(PublicKey, PublicKeyRequest);
+request_container!(Secrets, SecretsRequest);
+request_container!(SendSecrets, SendSecretsRequest);
+request_container!(AllowInvites, AllowInvitesRequest);
+request_container!(DeleteAccount, DeleteAccountRequest);
+request_container!(History, HistoryRequest);
+request_container!(EditMessage, EditMessageRequest);
+request_container!(DeleteMessage, DeleteMessageRequest);
+request_container!(React, ReactRequest);
+request_container!(Typing, TypingRequest);
+request_container!(MarkRead, MarkReadRequest);
+request_container!(DirectMessage, DirectMessageRequest);
+request_container!(AllowDirectMessages, AllowDirectMessagesRequest);
+request_container!(InviteKey, InviteKeyRequest);
+request_container!(Ban, BanRequest);
+request_container!(Unban, UnbanRequest);
+request_container!(Mute, MuteRequest);
+
+impl ResponseKind {
+    /// What a connection must have negotiated to be sent this response.
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::Ping(_)
+            | Self::Version(_)
+            | Self::Register(_)
+            | Self::Authenticate(_)
+            | Self::Message(_)
+            | Self::Error(_)
+            | Self::Create(_)
+            | Self::Disband(_)
+            | Self::Invite(_)
+            | Self::Invited(_)
+            | Self::Join(_)
+            | Self::Leave(_)
+            | Self::Kick(_)
+            | Self::Promote(_)
+            | Self::Update(_)
+            | Self::PublicKey(_)
+            | Self::Secrets(_)
+            | Self::SendSecrets(_)
+            | Self::Announce(_)
+            | Self::AllowInvites(_)
+            | Self::DeleteAccount(_) => Requirement::Version(1),
+            Self::List(list) => list.requirement(),
+            Self::Updated(updated) => updated.kind.requirement(),
+            Self::MemberChange(change) => change.kind.requirement(),
+            Self::History(_) => Requirement::Capability(HISTORY),
+            Self::MessageAck(_) => Requirement::Capability(MESSAGE_ACK),
+            Self::EditMessage(_)
+            | Self::MessageEdited(_)
+            | Self::DeleteMessage(_)
+            | Self::MessageDeleted(_) => Requirement::Capability(MESSAGE_EDIT),
+            Self::React(_)
+            | Self::ReactionChanged(_) => Requirement::Capability(REACTIONS),
+            Self::Typing(_) => Requirement::Capability(TYPING),
+            Self::MarkRead(_) => Requirement::Capability(READ_MARKERS),
+            Self::DirectMessage(_)
+            | Self::DirectMessageReceived(_)
+            | Self::AllowDirectMessages(_) => Requirement::Capability(DIRECT_MESSAGES),
+            Self::InviteKey(_) => Requirement::Capability(OFFLINE_INVITES),
+            Self::Ban(_)
+            | Self::Unban(_) => Requirement::Capability(BANS),
+            Self::Mute(_) => Requirement::Capability(MUTES),
+        }
+    }
+}
+
+macro_rules! response_container {
+    ($name:ident, $response:ty) => {
+        impl From<$response> for ResponseKind {
+            fn from(response: $response) -> Self {
+                ResponseKind::$name(response)
+            }
+        }
+    };
+}
+
+response_container!(Ping, PingResponse);
+response_container!(Version, VersionResponse);
+response_container!(Register, RegisterResponse);
+response_container!(Authenticate, AuthenticateResponse);
+response_container!(Message, MessageResponse);
+response_container!(Error, ErrorResponse);
+response_container!(Create, CreateResponse);
+response_container!(Disband, DisbandResponse);
+response_container!(Invite, InviteResponse);
+response_container!(Invited, InvitedResponse);
+response_container!(Join, JoinResponse);
+response_container!(Leave, LeaveResponse);
+response_container!(Kick, KickResponse);
+response_container!(List, ListResponse);
+response_container!(Promote, PromoteResponse);
+response_container!(Update, UpdateResponse);
+response_container!(Updated, UpdatedResponse);
+response_container!(PublicKey, PublicKeyResponse);
+response_container!(MemberChange, MemberChangeResponse);
+response_container!(Secrets, SecretsResponse);
+response_container!(SendSecrets, SendSecretsResponse);
+response_container!(Announce, AnnounceResponse);
+response_container!(AllowInvites, AllowInvitesResponse);
+response_container!(DeleteAccount, DeleteAccountResponse);
+response_container!(History, HistoryResponse);
+response_container!(MessageAck, MessageAckResponse);
+response_container!(EditMessage, EditMessageResponse);
+response_container!(MessageEdited, MessageEditedResponse);
+response_container!(DeleteMessage, DeleteMessageResponse);
+response_container!(MessageDeleted, MessageDeletedResponse);
+response_container!(React, ReactResponse);
+response_container!(ReactionChanged, ReactionChangedResponse);
+response_container!(Typing, TypingResponse);
+response_container!(MarkRead, MarkReadResponse);
+response_container!(DirectMessage, DirectMessageResponse);
+response_container!(DirectMessageReceived, DirectMessageReceivedResponse);
+response_container!(AllowDirectMessages, AllowDirectMessagesResponse);
+response_container!(InviteKey, InviteKeyResponse);
+response_container!(Ban, BanResponse);
+response_container!(Unban, UnbanResponse);
+response_container!(Mute, MuteResponse);
@@ -1,11 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-use crate::types::protocol::channel::Channel;
-use crate::util::redacted::Redacted;
+use crate::channel::Channel;
+use crate::redacted::Redacted;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateRequest {
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub name: Redacted<Vec<u8>>,
 }
 
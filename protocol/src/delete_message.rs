@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Deletes a message. Anyone may delete their own messages, and
+/// moderators may delete anyone's. Requires the
+/// [`MESSAGE_EDIT`](crate::MESSAGE_EDIT) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteMessageRequest {
+    pub channel: Uuid,
+    pub id: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteMessageResponse {
+    pub channel: Uuid,
+    pub id: u64,
+}
+
+/// Pushed to the channel's online members when a message is deleted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageDeletedResponse {
+    pub channel: Uuid,
+    pub id: u64,
+    pub deleter: String,
+    pub deleter_world: u16,
+}
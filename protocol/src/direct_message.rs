@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::redacted::Redacted;
+
+/// Sends a message to one character. The server only relays it, so the
+/// recipient has to be online and accepting direct messages. Requires the
+/// [`DIRECT_MESSAGES`](crate::DIRECT_MESSAGES) capability.
+///
+/// The message should be encrypted with a key agreed between your session
+/// key and the recipient's, as returned by a
+/// [`PublicKeyRequest`](crate::PublicKeyRequest).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectMessageRequest {
+    pub name: String,
+    pub world: u16,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+}
+
+/// Sent once the message has been passed on to the recipient.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectMessageResponse {
+    pub name: String,
+    pub world: u16,
+}
+
+/// Pushed to the recipient of a [`DirectMessageRequest`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DirectMessageReceivedResponse {
+    pub name: String,
+    pub world: u16,
+    /// The sender's session key, to agree the message key with.
+    #[serde(with = "crate::bytes")]
+    pub pk: Redacted<Vec<u8>>,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+}
+
+/// Whether other characters may send you direct messages this session. Off
+/// until asked for.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowDirectMessagesRequest {
+    pub allowed: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AllowDirectMessagesResponse {
+    pub allowed: bool,
+}
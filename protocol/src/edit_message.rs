@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::redacted::Redacted;
+
+/// Replaces the content of one of your own messages. Requires the
+/// [`MESSAGE_EDIT`](crate::MESSAGE_EDIT) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditMessageRequest {
+    pub channel: Uuid,
+    pub id: u64,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EditMessageResponse {
+    pub channel: Uuid,
+    pub id: u64,
+}
+
+/// Pushed to the channel's online members when a message is edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageEditedResponse {
+    pub channel: Uuid,
+    pub id: u64,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+    /// When the edit was made, in milliseconds since the Unix epoch.
+    pub timestamp: i64,
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use serde_repr::{Deserialize_repr, Serialize_repr};
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorResponse {
+    pub channel: Option<Uuid>,
+    pub error: String,
+    #[serde(default)]
+    pub code: ErrorCode,
+}
+
+impl ErrorResponse {
+    pub fn new(channel: impl Into<Option<Uuid>>, code: ErrorCode, error: impl Into<String>) -> Self {
+        ErrorResponse {
+            channel: channel.into(),
+            error: error.into(),
+            code,
+        }
+    }
+}
+
+/// A stable, machine-readable reason for a failure. The accompanying
+/// error string is only meant for display.
+///
+/// Values must never be reused or renumbered.
+#[derive(Debug, Clone, Copy, Default, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+#[repr(u16)]
+pub enum ErrorCode {
+    /// Also used for codes from a newer peer that this version doesn't know.
+    #[default]
+    #[serde(other)]
+    Unknown = 0,
+    NotLoggedIn = 1,
+    AlreadyLoggedIn = 2,
+    InvalidKey = 3,
+    NotInChannel = 4,
+    InsufficientRank = 5,
+    UserNotOnline = 6,
+    UserNotFound = 7,
+    UserNotInChannel = 8,
+    AlreadyInChannel = 9,
+    AlreadyInvited = 10,
+    NotInvited = 11,
+    CannotTargetSelf = 12,
+    InvalidRank = 13,
+    PromoteBeforeLeaving = 14,
+    LeaveChannelsFirst = 15,
+    NoOnlineMembers = 16,
+    RateLimited = 17,
+    UnsupportedVersion = 18,
+    UnsupportedRequest = 19,
+    NotImplemented = 20,
+    Internal = 21,
+    InvalidRequest = 22,
+    MessageNotFound = 23,
+    Banned = 24,
+    NotBanned = 25,
+    Muted = 26,
+    NotMuted = 27,
+}
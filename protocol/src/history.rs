@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::react::Reaction;
+use crate::redacted::Redacted;
+
+/// Asks for a page of a channel's stored messages. Requires the
+/// [`HISTORY`](crate::HISTORY) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryRequest {
+    pub channel: Uuid,
+    pub cursor: HistoryCursor,
+    /// The most messages to return. The server may return fewer.
+    pub limit: u32,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HistoryCursor {
+    /// The newest messages.
+    Latest,
+    /// Messages sent before the one with this id.
+    Before(u64),
+    /// Messages sent after the one with this id.
+    After(u64),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryResponse {
+    pub channel: Uuid,
+    /// Oldest first.
+    pub messages: Vec<HistoryMessage>,
+    /// Whether there are more messages past this page in the direction of
+    /// the cursor.
+    pub more: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryMessage {
+    pub id: u64,
+    pub sender: String,
+    pub world: u16,
+    /// When the server received the message, in milliseconds since the
+    /// Unix epoch.
+    pub timestamp: i64,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+    /// See [`MessageResponse::sequence`](crate::MessageResponse::sequence).
+    #[serde(default)]
+    pub sequence: u64,
+    /// When the message was last edited, if it has been, in milliseconds
+    /// since the Unix epoch.
+    #[serde(default)]
+    pub edited: Option<i64>,
+    /// See [`MessageRequest::reply_to`](crate::MessageRequest::reply_to).
+    #[serde(default)]
+    pub reply_to: Option<u64>,
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+}
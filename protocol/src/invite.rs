@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::channel::Channel;
+use crate::redacted::Redacted;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteRequest {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    #[serde(with = "crate::bytes")]
+    pub encrypted_secret: Redacted<Vec<u8>>,
+    /// Whether the secret is encrypted to the invitee's invite key rather
+    /// than their session key, in which case they needn't be online. See
+    /// [`InviteKeyRequest`](crate::InviteKeyRequest).
+    #[serde(default)]
+    pub invite_key: bool,
+    /// Seconds until the invite expires, or `None` to keep it until it's
+    /// accepted or declined.
+    #[serde(default)]
+    pub expires_in: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitedResponse {
+    pub channel: Channel,
+    pub name: String,
+    pub world: u16,
+    #[serde(with = "crate::bytes")]
+    pub pk: Redacted<Vec<u8>>,
+    #[serde(with = "crate::bytes")]
+    pub encrypted_secret: Redacted<Vec<u8>>,
+    /// See [`InviteRequest::invite_key`].
+    #[serde(default)]
+    pub invite_key: bool,
+}
+
+/// An invite to a channel that hasn't been accepted or declined yet.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingInvite {
+    pub name: String,
+    pub world: u16,
+    pub inviter: String,
+    pub inviter_world: u16,
+    /// Milliseconds since the Unix epoch.
+    pub created_at: i64,
+    /// Milliseconds since the Unix epoch, if the invite expires.
+    pub expires_at: Option<i64>,
+}
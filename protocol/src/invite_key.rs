@@ -0,0 +1,21 @@
+use serde::{Deserialize, Serialize};
+
+use crate::redacted::Redacted;
+
+/// Publishes the key others encrypt invites to while you're offline, or
+/// removes it if `pk` is `None`. Unlike the session key this is stored, so
+/// it should stay the same across sessions. Requires the
+/// [`OFFLINE_INVITES`](crate::OFFLINE_INVITES) capability.
+///
+/// Replacing the key drops any pending invites encrypted to the old one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteKeyRequest {
+    #[serde(with = "crate::bytes")]
+    pub pk: Option<Redacted<Vec<u8>>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InviteKeyResponse {
+    /// Whether a key is published now.
+    pub set: bool,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::types::protocol::channel::Channel;
+use crate::channel::Channel;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JoinRequest {
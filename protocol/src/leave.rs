@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::ErrorCode;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LeaveRequest {
     pub channel: Uuid,
@@ -10,6 +12,8 @@ pub struct LeaveRequest {
 pub struct LeaveResponse {
     pub channel: Uuid,
     pub error: Option<String>,
+    #[serde(default)]
+    pub code: Option<ErrorCode>,
 }
 
 impl LeaveResponse {
@@ -17,13 +21,15 @@ impl LeaveResponse {
         LeaveResponse {
             channel,
             error: None,
+            code: None,
         }
     }
 
-    pub fn error(channel: Uuid, error: impl Into<String>) -> Self {
+    pub fn error(channel: Uuid, code: ErrorCode, error: impl Into<String>) -> Self {
         LeaveResponse {
             channel,
             error: Some(error.into()),
+            code: Some(code),
         }
     }
 }
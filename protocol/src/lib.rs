@@ -2,23 +2,33 @@ pub use self::{
     allow_invites::*,
     announce::*,
     authenticate::*,
+    ban::*,
     container::*,
     create::*,
     delete_account::*,
+    delete_message::*,
+    direct_message::*,
     disband::*,
+    edit_message::*,
     error::*,
+    history::*,
     invite::*,
+    invite_key::*,
     join::*,
     kick::*,
     leave::*,
     list::*,
+    mark_read::*,
     member_change::*,
     message::*,
+    mute::*,
     ping::*,
     promote::*,
     public_key::*,
+    react::*,
     register::*,
     secrets::*,
+    typing::*,
     update::*,
     version::*,
 };
@@ -26,25 +36,38 @@ pub use self::{
 pub mod allow_invites;
 pub mod announce;
 pub mod authenticate;
+pub mod ban;
 pub mod container;
 pub mod create;
 pub mod delete_account;
+pub mod delete_message;
+pub mod direct_message;
 pub mod disband;
+pub mod edit_message;
 pub mod error;
+pub mod history;
 pub mod invite;
+pub mod invite_key;
 pub mod join;
 pub mod kick;
 pub mod leave;
 pub mod list;
+pub mod mark_read;
 pub mod member_change;
 pub mod message;
+pub mod mute;
 pub mod ping;
 pub mod promote;
 pub mod public_key;
+pub mod react;
 pub mod register;
 pub mod secrets;
+pub mod typing;
 pub mod update;
 pub mod version;
 
+pub mod bytes;
 pub mod channel;
-
+pub mod codec;
+pub mod lenient;
+pub mod redacted;
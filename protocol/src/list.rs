@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::channel::{Channel, ChannelMember, SimpleChannel};
+use crate::invite::PendingInvite;
+use crate::ban::ChannelBan;
+use crate::version::{BANS, INVITE_EXPIRY, Requirement};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListRequest {
+    All,
+    Channels,
+    Members(Uuid),
+    Invites,
+    /// A channel's pending invites. Moderators and up only.
+    PendingInvites(Uuid),
+    /// A channel's bans. Moderators and up only.
+    Bans(Uuid),
+}
+
+impl ListRequest {
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::All
+            | Self::Channels
+            | Self::Members(_)
+            | Self::Invites => Requirement::Version(1),
+            Self::PendingInvites(_) => Requirement::Capability(INVITE_EXPIRY),
+            Self::Bans(_) => Requirement::Capability(BANS),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListResponse {
+    All {
+        channels: Vec<Channel>,
+        invites: Vec<Channel>,
+    },
+    Channels(Vec<SimpleChannel>),
+    Members {
+        id: Uuid,
+        members: Vec<ChannelMember>,
+    },
+    Invites(Vec<SimpleChannel>),
+    PendingInvites {
+        id: Uuid,
+        invites: Vec<PendingInvite>,
+    },
+    Bans {
+        id: Uuid,
+        bans: Vec<ChannelBan>,
+    },
+}
+
+impl ListResponse {
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::All { .. }
+            | Self::Channels(_)
+            | Self::Members { .. }
+            | Self::Invites(_) => Requirement::Version(1),
+            Self::PendingInvites { .. } => Requirement::Capability(INVITE_EXPIRY),
+            Self::Bans { .. } => Requirement::Capability(BANS),
+        }
+    }
+}
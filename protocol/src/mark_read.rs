@@ -0,0 +1,18 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Moves your read marker in a channel forward to a message's sequence
+/// number. Requires the [`READ_MARKERS`](crate::READ_MARKERS) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkReadRequest {
+    pub channel: Uuid,
+    pub sequence: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkReadResponse {
+    pub channel: Uuid,
+    /// Where the marker is now. Markers never move backwards, so this may
+    /// be past the sequence number asked for.
+    pub sequence: u64,
+}
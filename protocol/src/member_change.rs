@@ -0,0 +1,75 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::channel::Rank;
+use crate::version::{BANS, INVITE_EXPIRY, MUTES, Requirement};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MemberChangeResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    pub kind: MemberChangeKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MemberChangeKind {
+    Invite {
+        inviter: String,
+        inviter_world: u16,
+    },
+    InviteDecline,
+    InviteCancel {
+        canceler: String,
+        canceler_world: u16,
+    },
+    Join,
+    Leave,
+    Promote {
+        rank: Rank,
+    },
+    Kick {
+        kicker: String,
+        kicker_world: u16,
+    },
+    /// The invite ran out before it was accepted.
+    InviteExpired,
+    Ban {
+        banner: String,
+        banner_world: u16,
+    },
+    Unban {
+        unbanner: String,
+        unbanner_world: u16,
+    },
+    Mute {
+        muter: String,
+        muter_world: u16,
+        /// See [`ChannelMember::muted_until`](crate::channel::ChannelMember::muted_until).
+        until: i64,
+    },
+    /// The mute was lifted, either by a moderator or because it ran out.
+    /// Mutes that run out are announced late, so stop treating the member
+    /// as muted at `until` regardless.
+    Unmute,
+}
+
+impl MemberChangeKind {
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::Invite { .. }
+            | Self::InviteDecline
+            | Self::InviteCancel { .. }
+            | Self::Join
+            | Self::Leave
+            | Self::Promote { .. }
+            | Self::Kick { .. } => Requirement::Version(1),
+            Self::InviteExpired => Requirement::Capability(INVITE_EXPIRY),
+            Self::Ban { .. }
+            | Self::Unban { .. } => Requirement::Capability(BANS),
+            Self::Mute { .. }
+            | Self::Unmute => Requirement::Capability(MUTES),
+        }
+    }
+}
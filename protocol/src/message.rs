@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use crate::redacted::Redacted;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageRequest {
+    pub channel: Uuid,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+    /// The id of an earlier message in the same channel this replies to.
+    #[serde(default)]
+    pub reply_to: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageResponse {
+    pub channel: Uuid,
+    pub sender: String,
+    pub world: u16,
+    #[serde(with = "crate::bytes")]
+    pub message: Redacted<Vec<u8>>,
+    /// Unique across the server. Zero only from servers too old to give
+    /// messages ids.
+    #[serde(default)]
+    pub id: u64,
+    /// When the server received the message, in milliseconds since the
+    /// Unix epoch.
+    #[serde(default)]
+    pub timestamp: i64,
+    /// Goes up by one with every message in the channel, so a jump of more
+    /// than one means messages were missed.
+    #[serde(default)]
+    pub sequence: u64,
+    /// See [`MessageRequest::reply_to`].
+    #[serde(default)]
+    pub reply_to: Option<u64>,
+}
+
+/// Sent in reply to a [`MessageRequest`] once the message has been
+/// accepted. Requires the [`MESSAGE_ACK`](crate::MESSAGE_ACK) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageAckResponse {
+    pub channel: Uuid,
+    /// See [`MessageResponse::id`].
+    pub id: u64,
+    /// See [`MessageResponse::sequence`].
+    pub sequence: u64,
+    /// Other members the message was sent to straight away.
+    pub live: u32,
+    /// Other members the message was stored for until they next log in.
+    pub queued: u32,
+}
@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Stops a member sending messages in a channel for a while. Moderators and
+/// up only, and only on members they outrank. Requires the
+/// [`MUTES`](crate::MUTES) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuteRequest {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    /// Seconds the mute lasts for. Zero lifts an existing mute.
+    pub duration: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MuteResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    /// See [`ChannelMember::muted_until`](crate::channel::ChannelMember::muted_until).
+    pub muted_until: Option<i64>,
+}
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::types::protocol::channel::Rank;
+use crate::channel::Rank;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PromoteRequest {
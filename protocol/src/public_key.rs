@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::util::redacted::Redacted;
+use crate::redacted::Redacted;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PublicKeyRequest {
@@ -11,6 +11,9 @@ pub struct PublicKeyRequest {
 pub struct PublicKeyResponse {
     pub name: String,
     pub world: u16,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub pk: Option<Redacted<Vec<u8>>>,
+    /// The character's stored invite key, whether they're online or not.
+    #[serde(default, with = "crate::bytes")]
+    pub invite_pk: Option<Redacted<Vec<u8>>>,
 }
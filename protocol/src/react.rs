@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::redacted::Redacted;
+
+/// Adds or removes a reaction on a message. The reaction is encrypted by
+/// the client, so reactions are told apart by their bytes: clients should
+/// encrypt them deterministically for the same reaction from different
+/// members to be counted together. Requires the
+/// [`REACTIONS`](crate::REACTIONS) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactRequest {
+    pub channel: Uuid,
+    pub id: u64,
+    #[serde(with = "crate::bytes")]
+    pub reaction: Redacted<Vec<u8>>,
+    /// Remove the reaction instead of adding it.
+    pub remove: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactResponse {
+    pub channel: Uuid,
+    pub id: u64,
+}
+
+/// Pushed to the channel's online members when someone adds or removes a
+/// reaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReactionChangedResponse {
+    pub channel: Uuid,
+    pub id: u64,
+    #[serde(with = "crate::bytes")]
+    pub reaction: Redacted<Vec<u8>>,
+    pub name: String,
+    pub world: u16,
+    pub removed: bool,
+}
+
+/// Everyone's uses of one reaction on a message.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reaction {
+    #[serde(with = "crate::bytes")]
+    pub reaction: Redacted<Vec<u8>>,
+    pub count: u32,
+    /// Whether the member asking is one of them.
+    pub mine: bool,
+}
@@ -2,8 +2,11 @@ use std::fmt::{Debug, Display, Formatter};
 use std::ops::{Deref, DerefMut};
 
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
+#[cfg(feature = "sqlx")]
 use sqlx::{Database, Encode, Type};
+#[cfg(feature = "sqlx")]
 use sqlx::database::HasArguments;
+#[cfg(feature = "sqlx")]
 use sqlx::encode::IsNull;
 
 #[repr(transparent)]
@@ -75,18 +78,7 @@ impl<'de, T: Deserialize<'de>> Deserialize<'de> for Redacted<T> {
     }
 }
 
-impl<T: serde_bytes::Serialize> serde_bytes::Serialize for Redacted<T> {
-    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        serde_bytes::Serialize::serialize(&self.0, serializer)
-    }
-}
-
-impl<'de, T: serde_bytes::Deserialize<'de>> serde_bytes::Deserialize<'de> for Redacted<T> {
-    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
-        serde_bytes::Deserialize::deserialize(deserializer).map(Redacted)
-    }
-}
-
+#[cfg(feature = "sqlx")]
 impl<'q, DB: Database, T: Encode<'q, DB>> Encode<'q, DB> for Redacted<T> {
     fn encode(self, buf: &mut <DB as HasArguments<'q>>::ArgumentBuffer) -> IsNull where Self: Sized {
         self.0.encode(buf)
@@ -105,6 +97,7 @@ impl<'q, DB: Database, T: Encode<'q, DB>> Encode<'q, DB> for Redacted<T> {
     }
 }
 
+#[cfg(feature = "sqlx")]
 impl<DB: Database, T: Type<DB>> Type<DB> for Redacted<T> {
     fn type_info() -> DB::TypeInfo {
         T::type_info()
@@ -1,5 +1,5 @@
 use serde::{Deserialize, Serialize};
-use crate::util::redacted::Redacted;
+use crate::redacted::Redacted;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterRequest {
@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::util::redacted::Redacted;
+use crate::redacted::Redacted;
 
 /// A user sends this request if they have lost their
 /// shared secret for a channel.
@@ -15,9 +15,9 @@ pub struct SecretsRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecretsResponse {
     pub channel: Uuid,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub pk: Redacted<Vec<u8>>,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub encrypted_shared_secret: Redacted<Vec<u8>>,
 }
 
@@ -30,7 +30,7 @@ pub struct SecretsResponse {
 pub struct SendSecretsResponse {
     pub channel: Uuid,
     pub request_id: Uuid,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub pk: Redacted<Vec<u8>>,
 }
 
@@ -41,6 +41,6 @@ pub struct SendSecretsResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SendSecretsRequest {
     pub request_id: Uuid,
-    #[serde(with = "serde_bytes")]
+    #[serde(with = "crate::bytes")]
     pub encrypted_shared_secret: Option<Redacted<Vec<u8>>>,
 }
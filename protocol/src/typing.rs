@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Tells the channel's other online members whether you're typing. Nothing
+/// is sent back unless the request fails. Updates within three seconds of
+/// the last one passed on fail with `RateLimited`. Requires the
+/// [`TYPING`](crate::TYPING) capability.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypingRequest {
+    pub channel: Uuid,
+    /// False when the member stops typing without sending anything.
+    pub typing: bool,
+}
+
+/// Pushed to the channel's other online members. Never stored.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TypingResponse {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+    pub typing: bool,
+    /// Milliseconds after which to stop showing the member as typing if no
+    /// further update arrives.
+    pub expires_in: u32,
+}
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
-use crate::util::redacted::Redacted;
+use crate::redacted::Redacted;
+use crate::version::{HISTORY, Requirement};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UpdateRequest {
@@ -11,7 +12,19 @@ pub struct UpdateRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum UpdateKind {
-    Name(#[serde(with = "serde_bytes")] Redacted<Vec<u8>>),
+    Name(#[serde(with = "crate::bytes")] Redacted<Vec<u8>>),
+    /// Seconds messages are kept for. `None` uses the server's retention,
+    /// which is also the most a channel can ask for.
+    Retention(Option<u64>),
+}
+
+impl UpdateKind {
+    pub fn requirement(&self) -> Requirement {
+        match self {
+            Self::Name(_) => Requirement::Version(1),
+            Self::Retention(_) => Requirement::Capability(HISTORY),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
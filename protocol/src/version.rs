@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+/// Lowest protocol version this server can speak.
+pub const MIN_VERSION: u32 = 1;
+/// Highest protocol version this server can speak.
+pub const MAX_VERSION: u32 = 1;
+/// Optional features this server can speak, offered during version
+/// negotiation.
+pub const CAPABILITIES: &[&str] = &[HISTORY, MESSAGE_ACK, MESSAGE_EDIT, REACTIONS, TYPING, READ_MARKERS, DIRECT_MESSAGES, OFFLINE_INVITES, INVITE_EXPIRY, BANS, MUTES];
+
+/// Stored message history and per-channel retention.
+pub const HISTORY: &str = "history";
+/// Replies to messages once they've been accepted.
+pub const MESSAGE_ACK: &str = "message_ack";
+/// Editing and deleting sent messages.
+pub const MESSAGE_EDIT: &str = "message_edit";
+/// Encrypted reactions on messages.
+pub const REACTIONS: &str = "reactions";
+/// Typing indicators.
+pub const TYPING: &str = "typing";
+/// Read markers per channel.
+pub const READ_MARKERS: &str = "read_markers";
+/// Direct messages between two characters.
+pub const DIRECT_MESSAGES: &str = "direct_messages";
+/// Invites encrypted to a stored key, so the invitee needn't be online.
+pub const OFFLINE_INVITES: &str = "offline_invites";
+/// Invites that expire, and listing a channel's pending invites.
+pub const INVITE_EXPIRY: &str = "invite_expiry";
+/// Per-channel bans.
+pub const BANS: &str = "bans";
+/// Timed mutes for channel members.
+pub const MUTES: &str = "mutes";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionRequest {
+    /// The highest version the client supports. Older clients only send
+    /// this field, meaning they support exactly this version.
+    pub version: u32,
+    /// The lowest version the client supports, if different to `version`.
+    #[serde(default)]
+    pub min_version: Option<u32>,
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VersionResponse {
+    /// The version negotiated for this connection.
+    pub version: u32,
+    /// The capabilities both sides support.
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// What a connection must have negotiated before a request or response
+/// kind may be exchanged over it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Requirement {
+    Version(u32),
+    Capability(&'static str),
+}
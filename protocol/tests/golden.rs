@@ -0,0 +1,352 @@
+//! Pins the exact bytes exchanged with the C# client. Requests are what the
+//! C# `RequestKindFormatter` writes, responses are what its
+//! `ResponseKindFormatter` reads. If one of these breaks, existing plugin
+//! installs break with it.
+
+use extrachat_protocol::{
+    codec,
+    channel::{ChannelMember, Rank, SimpleChannel},
+    ErrorCode,
+    ErrorResponse,
+    HistoryCursor,
+    ListRequest,
+    ListResponse,
+    MemberChangeKind,
+    MemberChangeResponse,
+    MessageAckResponse,
+    MessageResponse,
+    PublicKeyResponse,
+    RegisterResponse,
+    RequestKind,
+    Requirement,
+    ResponseContainer,
+    ResponseKind,
+    UpdatedResponse,
+    UpdateKind,
+    VersionResponse,
+};
+use uuid::Uuid;
+
+const CHANNEL: Uuid = Uuid::from_u128(0x0123456789abcdef0123456789abcdef);
+const CHANNEL_HEX: &str = "c4 10 01 23 45 67 89 ab cd ef 01 23 45 67 89 ab cd ef";
+
+fn hex(s: &str) -> Vec<u8> {
+    s.split_whitespace()
+        .map(|byte| u8::from_str_radix(byte, 16).unwrap())
+        .collect()
+}
+
+fn encode(number: u32, kind: impl Into<ResponseKind>) -> Vec<u8> {
+    codec::encode_response(&ResponseContainer {
+        number,
+        kind: kind.into(),
+    }).unwrap()
+}
+
+#[test]
+fn ping_request() {
+    // [1, {"ping": []}]
+    let req = codec::decode_request(&hex("92 01 81 a4 70 69 6e 67 90")).unwrap();
+    assert_eq!(req.number, 1);
+    assert!(matches!(req.kind, RequestKind::Ping(_)));
+}
+
+#[test]
+fn version_request_from_old_client() {
+    // [2, {"version": [1]}]
+    let req = codec::decode_request(&hex("92 02 81 a7 76 65 72 73 69 6f 6e 91 01")).unwrap();
+    match req.kind {
+        RequestKind::Version(version) => {
+            assert_eq!(version.version, 1);
+            assert_eq!(version.min_version, None);
+            assert!(version.capabilities.is_empty());
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn register_request() {
+    // [3, {"register": ["Aaa Bbb", 73, false]}]
+    let req = codec::decode_request(&hex("92 03 81 a8 72 65 67 69 73 74 65 72 93 a7 41 61 61 20 42 62 62 49 c2")).unwrap();
+    match req.kind {
+        RequestKind::Register(register) => {
+            assert_eq!(register.name, "Aaa Bbb");
+            assert_eq!(register.world, 73);
+            assert!(!register.challenge_completed);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn message_request() {
+    // [4, {"message": [channel, bin(01 02 03)]}]
+    let bytes = hex(&format!("92 04 81 a7 6d 65 73 73 61 67 65 92 {CHANNEL_HEX} c4 03 01 02 03"));
+    let req = codec::decode_request(&bytes).unwrap();
+    match req.kind {
+        RequestKind::Message(message) => {
+            assert_eq!(message.channel, CHANNEL);
+            assert_eq!(message.message.as_slice(), &[1, 2, 3]);
+            assert_eq!(message.reply_to, None);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+
+    // [4, {"message": [channel, bin(01 02 03), 9]}]
+    let bytes = hex(&format!("92 04 81 a7 6d 65 73 73 61 67 65 93 {CHANNEL_HEX} c4 03 01 02 03 09"));
+    let req = codec::decode_request(&bytes).unwrap();
+    assert!(matches!(req.kind, RequestKind::Message(message) if message.reply_to == Some(9)));
+}
+
+#[test]
+fn list_requests() {
+    // [5, {"list": "all"}]
+    let req = codec::decode_request(&hex("92 05 81 a4 6c 69 73 74 a3 61 6c 6c")).unwrap();
+    assert!(matches!(req.kind, RequestKind::List(ListRequest::All)));
+
+    // [6, {"list": {"members": channel}}]
+    let bytes = hex(&format!("92 06 81 a4 6c 69 73 74 81 a7 6d 65 6d 62 65 72 73 {CHANNEL_HEX}"));
+    let req = codec::decode_request(&bytes).unwrap();
+    assert!(matches!(req.kind, RequestKind::List(ListRequest::Members(id)) if id == CHANNEL));
+}
+
+#[test]
+fn unknown_request_keeps_number() {
+    // [7, {"from_the_future": []}]
+    let bytes = hex("92 07 81 af 66 72 6f 6d 5f 74 68 65 5f 66 75 74 75 72 65 90");
+    let failure = codec::decode_request(&bytes).unwrap_err();
+    assert_eq!(failure.number, Some(7));
+}
+
+#[test]
+fn trailing_fields_are_ignored() {
+    // [8, {"ping": ["extra"]}, "extra"]
+    let req = codec::decode_request(&hex("93 08 81 a4 70 69 6e 67 91 a5 65 78 74 72 61 a5 65 78 74 72 61")).unwrap();
+    assert_eq!(req.number, 8);
+    assert!(matches!(req.kind, RequestKind::Ping(_)));
+}
+
+#[test]
+fn history_request() {
+    // [12, {"history": [channel, "latest", 50]}]
+    let bytes = hex(&format!("92 0c 81 a7 68 69 73 74 6f 72 79 93 {CHANNEL_HEX} a6 6c 61 74 65 73 74 32"));
+    let req = codec::decode_request(&bytes).unwrap();
+    match &req.kind {
+        RequestKind::History(history) => {
+            assert_eq!(history.channel, CHANNEL);
+            assert!(matches!(history.cursor, HistoryCursor::Latest));
+            assert_eq!(history.limit, 50);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+    assert_eq!(req.kind.requirement(), Requirement::Capability(extrachat_protocol::HISTORY));
+
+    // [13, {"history": [channel, {"before": 7}, 50]}]
+    let bytes = hex(&format!("92 0d 81 a7 68 69 73 74 6f 72 79 93 {CHANNEL_HEX} 81 a6 62 65 66 6f 72 65 07 32"));
+    let req = codec::decode_request(&bytes).unwrap();
+    assert!(matches!(req.kind, RequestKind::History(history) if matches!(history.cursor, HistoryCursor::Before(7))));
+}
+
+#[test]
+fn new_update_kinds_are_gated() {
+    let name = ResponseKind::from(UpdatedResponse {
+        channel: CHANNEL,
+        kind: UpdateKind::Name(vec![1].into()),
+    });
+    assert_eq!(name.requirement(), Requirement::Version(1));
+
+    let retention = ResponseKind::from(UpdatedResponse {
+        channel: CHANNEL,
+        kind: UpdateKind::Retention(Some(3600)),
+    });
+    assert_eq!(retention.requirement(), Requirement::Capability(extrachat_protocol::HISTORY));
+}
+
+#[test]
+fn version_response() {
+    // [2, {"version": [1, []]}]
+    assert_eq!(
+        encode(2, VersionResponse {
+            version: 1,
+            capabilities: Vec::new(),
+        }),
+        hex("92 02 81 a7 76 65 72 73 69 6f 6e 92 01 90"),
+    );
+}
+
+#[test]
+fn error_response() {
+    // [9, {"error": [channel, "not in channel", 4]}]
+    assert_eq!(
+        encode(9, ErrorResponse::new(CHANNEL, ErrorCode::NotInChannel, "not in channel")),
+        hex(&format!("92 09 81 a5 65 72 72 6f 72 93 {CHANNEL_HEX} ae 6e 6f 74 20 69 6e 20 63 68 61 6e 6e 65 6c 04")),
+    );
+
+    // [10, {"error": [nil, "x", 1]}]
+    assert_eq!(
+        encode(10, ErrorResponse::new(None, ErrorCode::NotLoggedIn, "x")),
+        hex("92 0a 81 a5 65 72 72 6f 72 93 c0 a1 78 01"),
+    );
+}
+
+#[test]
+fn message_response() {
+    // older clients read the first four fields and skip the rest:
+    // [0, {"message": [channel, "Aaa Bbb", 73, bin(01 02 03), 5, 1000, 3, nil]}]
+    assert_eq!(
+        encode(0, MessageResponse {
+            channel: CHANNEL,
+            sender: "Aaa Bbb".into(),
+            world: 73,
+            message: vec![1, 2, 3].into(),
+            id: 5,
+            timestamp: 1000,
+            sequence: 3,
+            reply_to: None,
+        }),
+        hex(&format!("92 00 81 a7 6d 65 73 73 61 67 65 98 {CHANNEL_HEX} a7 41 61 61 20 42 62 62 49 c4 03 01 02 03 05 cd 03 e8 03 c0")),
+    );
+}
+
+#[test]
+fn message_ack_response() {
+    // [4, {"message_ack": [channel, 5, 3, 2, 1]}]
+    let ack = ResponseKind::from(MessageAckResponse {
+        channel: CHANNEL,
+        id: 5,
+        sequence: 3,
+        live: 2,
+        queued: 1,
+    });
+    assert_eq!(ack.requirement(), Requirement::Capability(extrachat_protocol::MESSAGE_ACK));
+    assert_eq!(
+        encode(4, ack),
+        hex(&format!("92 04 81 ab 6d 65 73 73 61 67 65 5f 61 63 6b 95 {CHANNEL_HEX} 05 03 02 01")),
+    );
+}
+
+#[test]
+fn message_response_from_older_server() {
+    // [0, {"message": [channel, "Aaa Bbb", 73, bin(01 02 03)]}]
+    let bytes = hex(&format!("92 00 81 a7 6d 65 73 73 61 67 65 94 {CHANNEL_HEX} a7 41 61 61 20 42 62 62 49 c4 03 01 02 03"));
+    match codec::decode_response(&bytes).unwrap().kind {
+        ResponseKind::Message(message) => {
+            assert_eq!(message.world, 73);
+            assert_eq!(message.id, 0);
+            assert_eq!(message.sequence, 0);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn member_change_responses() {
+    // unit kinds are plain strings: [0, {"member_change": [channel, "Aaa Bbb", 73, "join"]}]
+    assert_eq!(
+        encode(0, MemberChangeResponse {
+            channel: CHANNEL,
+            name: "Aaa Bbb".into(),
+            world: 73,
+            kind: MemberChangeKind::Join,
+        }),
+        hex(&format!("92 00 81 ad 6d 65 6d 62 65 72 5f 63 68 61 6e 67 65 94 {CHANNEL_HEX} a7 41 61 61 20 42 62 62 49 a4 6a 6f 69 6e")),
+    );
+
+    // struct kinds are single-entry maps of arrays:
+    // [0, {"member_change": [channel, "Aaa Bbb", 73, {"promote": [2]}]}]
+    assert_eq!(
+        encode(0, MemberChangeResponse {
+            channel: CHANNEL,
+            name: "Aaa Bbb".into(),
+            world: 73,
+            kind: MemberChangeKind::Promote {
+                rank: Rank::Moderator,
+            },
+        }),
+        hex(&format!("92 00 81 ad 6d 65 6d 62 65 72 5f 63 68 61 6e 67 65 94 {CHANNEL_HEX} a7 41 61 61 20 42 62 62 49 81 a7 70 72 6f 6d 6f 74 65 91 02")),
+    );
+    // kinds old clients can't parse are only sent to those that asked
+    let expired = ResponseKind::from(MemberChangeResponse {
+        channel: CHANNEL,
+        name: "Aaa Bbb".into(),
+        world: 73,
+        kind: MemberChangeKind::InviteExpired,
+    });
+    assert_eq!(expired.requirement(), Requirement::Capability(extrachat_protocol::INVITE_EXPIRY));
+}
+
+#[test]
+fn list_response() {
+    // [5, {"list": {"channels": [[channel, bin(aa), 3, 7, 2]]}}]
+    assert_eq!(
+        encode(5, ListResponse::Channels(vec![SimpleChannel {
+            id: CHANNEL,
+            name: vec![0xaa],
+            rank: Rank::Admin,
+            last_read: 7,
+            unread: 2,
+        }])),
+        hex(&format!("92 05 81 a4 6c 69 73 74 81 a8 63 68 61 6e 6e 65 6c 73 91 95 {CHANNEL_HEX} c4 01 aa 03 07 02")),
+    );
+}
+
+#[test]
+fn register_response() {
+    // [3, {"register": {"challenge": ["abc"]}}]
+    assert_eq!(
+        encode(3, RegisterResponse::Challenge {
+            challenge: "abc".into(),
+        }),
+        hex("92 03 81 a8 72 65 67 69 73 74 65 72 81 a9 63 68 61 6c 6c 65 6e 67 65 91 a3 61 62 63"),
+    );
+
+    // [3, {"register": "failure"}]
+    assert_eq!(
+        encode(3, RegisterResponse::Failure),
+        hex("92 03 81 a8 72 65 67 69 73 74 65 72 a7 66 61 69 6c 75 72 65"),
+    );
+}
+
+#[test]
+fn public_key_response() {
+    // [11, {"public_key": ["Aaa Bbb", 73, bin(01 02), nil]}]
+    assert_eq!(
+        encode(11, PublicKeyResponse {
+            name: "Aaa Bbb".into(),
+            world: 73,
+            pk: Some(vec![1, 2].into()),
+            invite_pk: None,
+        }),
+        hex("92 0b 81 aa 70 75 62 6c 69 63 5f 6b 65 79 94 a7 41 61 61 20 42 62 62 49 c4 02 01 02 c0"),
+    );
+
+    // [11, {"public_key": ["Aaa Bbb", 73, nil, bin(03)]}]
+    assert_eq!(
+        encode(11, PublicKeyResponse {
+            name: "Aaa Bbb".into(),
+            world: 73,
+            pk: None,
+            invite_pk: Some(vec![3].into()),
+        }),
+        hex("92 0b 81 aa 70 75 62 6c 69 63 5f 6b 65 79 94 a7 41 61 61 20 42 62 62 49 c0 c4 01 03"),
+    );
+}
+
+#[test]
+fn members_response() {
+    // [6, {"list": {"members": [channel, [["Aaa Bbb", 73, 1, true, 1000]]]}}]
+    assert_eq!(
+        encode(6, ListResponse::Members {
+            id: CHANNEL,
+            members: vec![ChannelMember {
+                name: "Aaa Bbb".into(),
+                world: 73,
+                rank: Rank::Member,
+                online: true,
+                muted_until: Some(1000),
+            }],
+        }),
+        hex(&format!("92 06 81 a4 6c 69 73 74 81 a7 6d 65 6d 62 65 72 73 92 {CHANNEL_HEX} 91 95 a7 41 61 61 20 42 62 62 49 01 c3 cd 03 e8")),
+    );
+}
@@ -0,0 +1,156 @@
+//! The JSON text-frame encoding, as sent by e.g. `websocat` or a browser.
+
+use extrachat_protocol::{
+    BanRequest,
+    codec,
+    DirectMessageRequest,
+    ErrorCode,
+    ErrorResponse,
+    ListRequest,
+    MessageResponse,
+    PublicKeyResponse,
+    ReactRequest,
+    RequestKind,
+    ResponseContainer,
+    ResponseKind,
+    SendSecretsRequest,
+};
+use uuid::Uuid;
+
+const CHANNEL: Uuid = Uuid::from_u128(0x0123456789abcdef0123456789abcdef);
+
+fn encode(number: u32, kind: impl Into<ResponseKind>) -> String {
+    codec::encode_response_json(&ResponseContainer {
+        number,
+        kind: kind.into(),
+    }).unwrap()
+}
+
+#[test]
+fn requests() {
+    let req = codec::decode_request_json(r#"{"number": 1, "kind": {"ping": {}}}"#).unwrap();
+    assert_eq!(req.number, 1);
+    assert!(matches!(req.kind, RequestKind::Ping(_)));
+
+    let req = codec::decode_request_json(r#"{"number": 2, "kind": {"list": "all"}}"#).unwrap();
+    assert!(matches!(req.kind, RequestKind::List(ListRequest::All)));
+
+    let req = codec::decode_request_json(r#"{"number": 3, "kind": {"message": {"channel": "01234567-89ab-cdef-0123-456789abcdef", "message": "AQID"}}}"#).unwrap();
+    match req.kind {
+        RequestKind::Message(message) => {
+            assert_eq!(message.channel, CHANNEL);
+            assert_eq!(message.message.as_slice(), &[1, 2, 3]);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+
+    let req = codec::decode_request_json(r#"{"number": 4, "kind": {"send_secrets": {"request_id": "01234567-89ab-cdef-0123-456789abcdef", "encrypted_shared_secret": null}}}"#).unwrap();
+    assert!(matches!(req.kind, RequestKind::SendSecrets(SendSecretsRequest { encrypted_shared_secret: None, .. })));
+}
+
+#[test]
+fn unknown_fields_are_ignored() {
+    let req = codec::decode_request_json(r#"{"number": 5, "kind": {"version": {"version": 1, "from_the_future": true}}}"#).unwrap();
+    assert!(matches!(req.kind, RequestKind::Version(_)));
+}
+
+#[test]
+fn bad_requests_keep_number() {
+    let failure = codec::decode_request_json(r#"{"number": 6, "kind": {"from_the_future": {}}}"#).unwrap_err();
+    assert_eq!(failure.number, Some(6));
+
+    let failure = codec::decode_request_json(r#"{"number": 7, "kind": {"message": {"channel": "01234567-89ab-cdef-0123-456789abcdef", "message": "not base64!"}}}"#).unwrap_err();
+    assert_eq!(failure.number, Some(7));
+
+    let failure = codec::decode_request_json("not json").unwrap_err();
+    assert_eq!(failure.number, None);
+}
+
+#[test]
+fn responses() {
+    assert_eq!(
+        encode(0, MessageResponse {
+            channel: CHANNEL,
+            sender: "Aaa Bbb".into(),
+            world: 73,
+            message: vec![1, 2, 3].into(),
+            id: 5,
+            timestamp: 1000,
+            sequence: 3,
+            reply_to: Some(4),
+        }),
+        r#"{"number":0,"kind":{"message":{"channel":"01234567-89ab-cdef-0123-456789abcdef","sender":"Aaa Bbb","world":73,"message":"AQID","id":5,"timestamp":1000,"sequence":3,"reply_to":4}}}"#,
+    );
+
+    assert_eq!(
+        encode(8, ErrorResponse::new(None, ErrorCode::NotLoggedIn, "not logged in")),
+        r#"{"number":8,"kind":{"error":{"channel":null,"error":"not logged in","code":1}}}"#,
+    );
+
+    assert_eq!(
+        encode(9, PublicKeyResponse {
+            name: "Aaa Bbb".into(),
+            world: 73,
+            pk: None,
+            invite_pk: None,
+        }),
+        r#"{"number":9,"kind":{"public_key":{"name":"Aaa Bbb","world":73,"pk":null,"invite_pk":null}}}"#,
+    );
+}
+
+#[test]
+fn responses_round_trip() {
+    let json = encode(10, PublicKeyResponse {
+        name: "Aaa Bbb".into(),
+        world: 73,
+        pk: Some(vec![0xff; 32].into()),
+        invite_pk: None,
+    });
+
+    match codec::decode_response_json(&json).unwrap().kind {
+        ResponseKind::PublicKey(resp) => assert_eq!(resp.pk.unwrap().as_slice(), &[0xff; 32]),
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn react_request() {
+    let req = codec::decode_request_json(r#"{"number": 11, "kind": {"react": {"channel": "01234567-89ab-cdef-0123-456789abcdef", "id": 5, "reaction": "AQID", "remove": false}}}"#).unwrap();
+    match req.kind {
+        RequestKind::React(ReactRequest { channel, id, reaction, remove }) => {
+            assert_eq!(channel, CHANNEL);
+            assert_eq!(id, 5);
+            assert_eq!(reaction.as_slice(), &[1, 2, 3]);
+            assert!(!remove);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn direct_message_request() {
+    let req = codec::decode_request_json(r#"{"number": 12, "kind": {"direct_message": {"name": "Aaa Bbb", "world": 73, "message": "AQID"}}}"#).unwrap();
+    match req.kind {
+        RequestKind::DirectMessage(DirectMessageRequest { name, world, message }) => {
+            assert_eq!(name, "Aaa Bbb");
+            assert_eq!(world, 73);
+            assert_eq!(message.as_slice(), &[1, 2, 3]);
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
+
+#[test]
+fn ban_request() {
+    let req = codec::decode_request_json(r#"{"number": 13, "kind": {"ban": {"channel": "01234567-89ab-cdef-0123-456789abcdef", "name": "Aaa Bbb", "world": 73, "reason": null, "expires_in": 3600}}}"#).unwrap();
+    match req.kind {
+        RequestKind::Ban(BanRequest { channel, name, world, reason, expires_in }) => {
+            assert_eq!(channel, CHANNEL);
+            assert_eq!(name, "Aaa Bbb");
+            assert_eq!(world, 73);
+            assert!(reason.is_none());
+            assert_eq!(expires_in, Some(3600));
+        }
+        kind => panic!("unexpected kind {kind:?}"),
+    }
+}
@@ -0,0 +1,7 @@
+-- the sequence number of the last message each member has read
+alter table user_channels
+    add column last_read integer not null default 0;
+
+-- don't count everything sent before now as unread
+update user_channels
+set last_read = (select last_sequence from channels where channels.id = user_channels.channel_id);
@@ -0,0 +1,9 @@
+-- a key each user publishes for invites sent while they're offline
+alter table users
+    add column invite_pk blob;
+
+-- invites encrypted to that key, kept until they're accepted or declined
+alter table channel_invites
+    add column encrypted_secret blob;
+alter table channel_invites
+    add column inviter_pk blob;
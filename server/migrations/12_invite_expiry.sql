@@ -0,0 +1,26 @@
+-- sqlite can't add a column defaulting to the current time, so rebuild the
+-- table. invites that already exist count as sent now
+create table channel_invites_new
+(
+    channel_id       text            not null references channels (id) on delete cascade,
+    invited          unsigned bigint not null references users (lodestone_id) on delete cascade,
+    inviter          unsigned bigint not null references users (lodestone_id) on delete cascade,
+    encrypted_secret blob,
+    inviter_pk       blob,
+    created_at       timestamp       not null default current_timestamp,
+    expires_at       timestamp,
+
+    primary key (channel_id, invited)
+);
+
+insert into channel_invites_new (channel_id, invited, inviter, encrypted_secret, inviter_pk)
+select channel_id, invited, inviter, encrypted_secret, inviter_pk
+from channel_invites;
+
+drop table channel_invites;
+alter table channel_invites_new
+    rename to channel_invites;
+
+create index channel_invites_channel_id_idx on channel_invites (channel_id);
+create index channel_invites_channel_id_invited_idx on channel_invites (channel_id, invited);
+create index channel_invites_expires_at_idx on channel_invites (expires_at);
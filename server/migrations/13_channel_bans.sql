@@ -0,0 +1,15 @@
+create table channel_bans
+(
+    channel_id   text            not null references channels (id) on delete cascade,
+    lodestone_id unsigned bigint not null references users (lodestone_id) on delete cascade,
+    banner       unsigned bigint not null references users (lodestone_id) on delete cascade,
+    -- encrypted with the channel's secret
+    reason       blob,
+    created_at   timestamp       not null default current_timestamp,
+    expires_at   timestamp,
+
+    primary key (channel_id, lodestone_id)
+);
+
+create index channel_bans_channel_id_idx on channel_bans (channel_id);
+create index channel_bans_expires_at_idx on channel_bans (expires_at);
@@ -0,0 +1,3 @@
+-- when each member's mute is lifted, if they're muted
+alter table user_channels
+    add column muted_until timestamp;
@@ -0,0 +1,20 @@
+create table messages
+(
+    id         integer         not null primary key autoincrement,
+    channel_id text            not null references channels (id) on delete cascade,
+    sender     unsigned bigint not null references users (lodestone_id) on delete cascade,
+    message    blob            not null,
+    created_at timestamp       not null default current_timestamp
+);
+
+create index messages_channel_id_idx on messages (channel_id);
+create index messages_created_at_idx on messages (created_at);
+
+-- messages waiting for members that were offline when they were sent
+create table pending_messages
+(
+    lodestone_id unsigned bigint not null references users (lodestone_id) on delete cascade,
+    message_id   integer         not null references messages (id) on delete cascade,
+
+    primary key (lodestone_id, message_id)
+);
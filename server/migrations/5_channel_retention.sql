@@ -0,0 +1,4 @@
+-- seconds messages are kept for in this channel, or null for the server's
+-- retention
+alter table channels
+    add column retention integer;
@@ -0,0 +1,6 @@
+-- the sequence number given to the last message sent in each channel
+alter table channels
+    add column last_sequence integer not null default 0;
+
+alter table messages
+    add column sequence integer not null default 0;
@@ -0,0 +1,2 @@
+alter table messages
+    add column edited_at timestamp;
@@ -0,0 +1,2 @@
+alter table messages
+    add column reply_to integer references messages (id) on delete set null;
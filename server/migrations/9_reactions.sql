@@ -0,0 +1,12 @@
+create table message_reactions
+(
+    message_id   integer         not null references messages (id) on delete cascade,
+    lodestone_id unsigned bigint not null references users (lodestone_id) on delete cascade,
+    -- sha3 of the encrypted reaction, to tell reactions apart
+    hash         text            not null,
+    reaction     blob            not null,
+
+    primary key (message_id, lodestone_id, hash)
+);
+
+create index message_reactions_message_id_idx on message_reactions (message_id);
@@ -0,0 +1,94 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{debug, error};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio::time::Instant;
+
+use crate::{ResponseContainer, State};
+use crate::types::protocol::{MemberChangeKind, MemberChangeResponse, ResponseKind};
+
+/// Periodically removes stored data that has outlived its retention,
+/// invites, bans and mutes that have expired, and stale typing updates.
+pub fn spawn(state: Arc<RwLock<State>>) -> JoinHandle<()> {
+    const INTERVAL: Duration = Duration::from_secs(10 * 60);
+    // mutes are checked more often, since they're announced as lifted
+    // when they're cleared
+    const MUTE_INTERVAL: Duration = Duration::from_secs(30);
+
+    tokio::task::spawn(async move {
+        let mut last_full: Option<Instant> = None;
+        loop {
+            if last_full.is_none_or(|last| last.elapsed() >= INTERVAL) {
+                last_full = Some(Instant::now());
+
+                match crate::types::message::remove_expired(&state).await {
+                    Ok(removed) => debug!("removed {} expired messages", removed),
+                    Err(e) => error!("error removing expired messages: {:?}", e),
+                }
+
+                match crate::types::ban::remove_expired(&state).await {
+                    Ok(removed) => debug!("removed {} expired bans", removed),
+                    Err(e) => error!("error removing expired bans: {:?}", e),
+                }
+
+                let removed = crate::handlers::remove_stale_typing(&*state.read().await);
+                debug!("forgot {} stale typing updates", removed);
+
+                match remove_expired_invites(&state).await {
+                    Ok(removed) => debug!("removed {} expired invites", removed),
+                    Err(e) => error!("error removing expired invites: {:?}", e),
+                }
+            }
+
+            match remove_expired_mutes(&state).await {
+                Ok(removed) => debug!("lifted {} expired mutes", removed),
+                Err(e) => error!("error lifting expired mutes: {:?}", e),
+            }
+
+            tokio::time::sleep(MUTE_INTERVAL).await;
+        }
+    })
+}
+
+async fn remove_expired_mutes(state: &RwLock<State>) -> anyhow::Result<usize> {
+    let expired = crate::types::mute::remove_expired(state).await?;
+
+    for mute in &expired {
+        crate::util::send_to_all(state, mute.channel, 0, MemberChangeResponse {
+            channel: mute.channel,
+            name: mute.name.clone(),
+            world: mute.world,
+            kind: MemberChangeKind::Unmute,
+        }).await?;
+    }
+
+    Ok(expired.len())
+}
+
+async fn remove_expired_invites(state: &RwLock<State>) -> anyhow::Result<usize> {
+    let expired = crate::types::channel::remove_expired_invites(state).await?;
+
+    for invite in &expired {
+        let change = MemberChangeResponse {
+            channel: invite.channel,
+            name: invite.name.clone(),
+            world: invite.world,
+            kind: MemberChangeKind::InviteExpired,
+        };
+
+        // no longer invited, so not reached by sending to the channel
+        let invited = state.read().await.clients.get(&invite.lodestone_id).cloned();
+        if let Some(client) = invited {
+            client.read().await.tx.push(ResponseContainer {
+                number: 0,
+                kind: ResponseKind::MemberChange(change.clone()),
+            });
+        }
+
+        crate::util::send_to_all(state, invite.channel, 0, change).await?;
+    }
+
+    Ok(expired.len())
+}
@@ -0,0 +1,13 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+
+use crate::{ClientState, Outbound, State, util};
+use crate::types::protocol::{AllowDirectMessagesRequest, AllowDirectMessagesResponse};
+
+pub async fn allow_direct_messages(_state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: AllowDirectMessagesRequest) -> anyhow::Result<()> {
+    client_state.write().await.allow_direct_messages = req.allowed;
+    util::send(out, number, AllowDirectMessagesResponse {
+        allowed: req.allowed,
+    }).await
+}
@@ -3,14 +3,15 @@ use std::sync::Arc;
 
 use anyhow::Context;
 use chrono::{Duration, Utc};
-use log::trace;
+use log::{error, trace};
 use tokio::sync::RwLock;
 
-use crate::{AuthenticateRequest, AuthenticateResponse, ClientState, State, User, util, World, WsStream};
+use crate::{AuthenticateRequest, AuthenticateResponse, ClientState, Outbound, ResponseContainer, State, User, util, World};
+use crate::types::protocol::{ErrorCode, OFFLINE_INVITES, Requirement};
 
-pub async fn authenticate(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: AuthenticateRequest) -> anyhow::Result<()> {
+pub async fn authenticate(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: AuthenticateRequest) -> anyhow::Result<()> {
     if client_state.read().await.user.is_some() {
-        return util::send(conn, number, AuthenticateResponse::error("already logged in")).await;
+        return util::send(out, number, AuthenticateResponse::error(ErrorCode::AlreadyLoggedIn, "already logged in")).await;
     }
 
     let key = prefixed_api_key::parse(&*req.key)
@@ -27,7 +28,7 @@ pub async fn authenticate(state: Arc<RwLock<State>>, client_state: Arc<RwLock<Cl
         .context("could not query database for user")?;
     let user = match user {
         Some(u) => u,
-        None => return util::send(conn, number, AuthenticateResponse::error("invalid key")).await,
+        None => return util::send(out, number, AuthenticateResponse::error(ErrorCode::InvalidKey, "invalid key")).await,
     };
 
     let world = World::from_str(&user.world).map_err(|_| anyhow::anyhow!("invalid world in db"))?;
@@ -65,5 +66,48 @@ pub async fn authenticate(state: Arc<RwLock<State>>, client_state: Arc<RwLock<Cl
         state.read().await.updater_tx.send(user.lodestone_id).ok();
     }
 
-    util::send(conn, number, AuthenticateResponse::success()).await
+    util::send(out, number, AuthenticateResponse::success()).await?;
+
+    // catch up on whatever was sent while they were away. these are sent
+    // rather than pushed so a burst of live messages can't drop them
+    let mut pending = Vec::new();
+    for message in crate::types::message::get_pending(&state, user.lodestone_id as u64).await? {
+        let id = message.id;
+        let written = out.send_tracked(ResponseContainer {
+            number: 0,
+            kind: message.into(),
+        })?;
+        pending.push((id, written));
+    }
+
+    // and only forget them once they've been written, without holding up
+    // the connection's other requests meanwhile
+    if !pending.is_empty() {
+        let state = Arc::clone(&state);
+        let lodestone_id = user.lodestone_id as u64;
+        tokio::task::spawn(async move {
+            let mut delivered = Vec::with_capacity(pending.len());
+            for (id, written) in pending {
+                if written.await.is_err() {
+                    break;
+                }
+
+                delivered.push(id);
+            }
+
+            if let Err(e) = crate::types::message::remove_pending(&state, lodestone_id, &delivered).await {
+                error!("could not remove delivered pending messages: {:?}", e);
+            }
+        });
+    }
+
+    // and on invites sent to their invite key. these stay until accepted or
+    // declined, so they come again every login
+    if client_state.read().await.supports(Requirement::Capability(OFFLINE_INVITES)) {
+        for invite in crate::types::channel::get_pending_invites(&state, user.lodestone_id as u64).await? {
+            util::send(out, 0, invite).await?;
+        }
+    }
+
+    Ok(())
 }
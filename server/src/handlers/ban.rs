@@ -0,0 +1,84 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{BanRequest, BanResponse, ErrorCode, MemberChangeKind, MemberChangeResponse, ResponseKind};
+use crate::types::protocol::channel::Rank;
+use crate::util::send;
+
+pub async fn ban(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: BanRequest) -> Result<()> {
+    let user = match &client_state.read().await.user {
+        Some(user) => user.clone(),
+        None => return Ok(()),
+    };
+
+    let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
+        Some(rank) if rank >= Rank::Moderator => rank,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
+    };
+
+    let target_id = match state.read().await.get_id(&state, &req.name, req.world).await {
+        Some(id) => id,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotFound, "user not found")).await,
+    };
+    let target_id_i = target_id as i64;
+
+    if target_id == user.lodestone_id {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::CannotTargetSelf, "cannot ban self")).await;
+    }
+
+    // anyone not in the channel can be banned, but members only by someone
+    // who outranks them
+    let channel_id_str = req.channel.as_simple().to_string();
+    let target_rank: Option<Rank> = sqlx::query!(
+        // language=sqlite
+        "select rank from user_channels where channel_id = ? and lodestone_id = ?",
+        channel_id_str,
+        target_id_i,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not query database for rank")?
+        .map(|row| Rank::from_u8(row.rank as u8));
+
+    if matches!(target_rank, Some(target) if target >= rank) {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "cannot ban someone of equal or higher rank")).await;
+    }
+
+    // clamped so a silly expiry can't overflow
+    let expires_at = req.expires_in
+        .map(|secs| Utc::now().naive_utc() + Duration::seconds(secs.min(1000 * 365 * 24 * 60 * 60) as i64));
+    let reason = req.reason.as_ref().map(|reason| reason.as_inner().as_slice());
+    crate::types::ban::ban(&state, req.channel, target_id, user.lodestone_id, reason, expires_at).await?;
+
+    let change = MemberChangeResponse {
+        channel: req.channel,
+        name: req.name.clone(),
+        world: req.world,
+        kind: MemberChangeKind::Ban {
+            banner: user.name,
+            banner_world: crate::util::id_from_world(user.world),
+        },
+    };
+
+    // no longer in the channel, so not reached by sending to it
+    let target = state.read().await.clients.get(&target_id).cloned();
+    if let Some(client) = target {
+        client.read().await.tx.push(ResponseContainer {
+            number: 0,
+            kind: ResponseKind::MemberChange(change.clone()),
+        });
+    }
+
+    crate::util::send_to_all(&state, req.channel, 0, change).await?;
+
+    send(out, number, BanResponse {
+        channel: req.channel,
+        name: req.name,
+        world: req.world,
+    }).await
+}
@@ -4,11 +4,11 @@ use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::{ClientState, ErrorResponse, State, WsStream};
-use crate::types::protocol::{CreateRequest, CreateResponse};
-use crate::types::protocol::channel::{Channel, Rank};
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{CreateRequest, CreateResponse, ErrorCode};
+use crate::types::protocol::channel::Rank;
 
-pub async fn create(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: CreateRequest) -> Result<()> {
+pub async fn create(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: CreateRequest) -> Result<()> {
     let id = Uuid::new_v4();
     let id_str = id.as_simple().to_string();
 
@@ -40,14 +40,14 @@ pub async fn create(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
         .await
         .context("could not add user to channel")?;
 
-    let channel = match Channel::get(&state, id).await? {
+    let channel = match crate::types::channel::get(&state, id).await? {
         Some(c) => c,
         None => {
-            return crate::util::send(conn, number, ErrorResponse::new(None, "could not get newly-created channel")).await;
+            return crate::util::send(out, number, ErrorResponse::new(None, ErrorCode::Internal, "could not get newly-created channel")).await;
         }
     };
 
-    crate::util::send(conn, number, CreateResponse {
+    crate::util::send(out, number, CreateResponse {
         channel,
     }).await?;
 
@@ -3,13 +3,13 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, State, WsStream};
-use crate::types::protocol::{DeleteAccountRequest, DeleteAccountResponse};
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{DeleteAccountRequest, DeleteAccountResponse, ErrorCode};
 
-pub async fn delete_account(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, _req: DeleteAccountRequest) -> Result<()> {
+pub async fn delete_account(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, _req: DeleteAccountRequest) -> Result<()> {
     let id = match client_state.read().await.lodestone_id() {
         Some(id) => id,
-        None => return crate::util::send(conn, number, ErrorResponse::new(None, "no Lodestone ID? this is a bug")).await,
+        None => return crate::util::send(out, number, ErrorResponse::new(None, ErrorCode::Internal, "no Lodestone ID? this is a bug")).await,
     };
     let lodestone_id = id as i64;
 
@@ -23,7 +23,7 @@ pub async fn delete_account(state: Arc<RwLock<State>>, client_state: Arc<RwLock<
         .context("could not get channel count")?;
 
     if channels.count > 0 {
-        return crate::util::send(conn, number, ErrorResponse::new(None, "leave all linkshells first")).await;
+        return crate::util::send(out, number, ErrorResponse::new(None, ErrorCode::LeaveChannelsFirst, "leave all linkshells first")).await;
     }
 
     sqlx::query!(
@@ -35,5 +35,5 @@ pub async fn delete_account(state: Arc<RwLock<State>>, client_state: Arc<RwLock<
         .await
         .context("could not delete user")?;
 
-    crate::util::send(conn, number, DeleteAccountResponse {}).await
+    crate::util::send(out, number, DeleteAccountResponse {}).await
 }
@@ -0,0 +1,44 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, Rank, State};
+use crate::types::protocol::{DeleteMessageRequest, DeleteMessageResponse, ErrorCode, MessageDeletedResponse};
+use crate::util::send;
+
+pub async fn delete_message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: DeleteMessageRequest) -> Result<()> {
+    let (lodestone_id, name, world) = match &client_state.read().await.user {
+        Some(u) => (u.lodestone_id, u.name.clone(), u.world),
+        None => return Ok(()),
+    };
+
+    let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
+        Some(rank) => rank,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
+    };
+
+    match crate::types::message::origin(&state, req.id).await? {
+        Some(origin) if origin.channel == req.channel => {
+            // moderators can delete anyone's messages
+            if origin.sender != lodestone_id && rank < Rank::Moderator {
+                return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await;
+            }
+        }
+        _ => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::MessageNotFound, "no such message")).await,
+    }
+
+    crate::types::message::delete(&state, req.id).await?;
+
+    crate::util::send_to_members(&state, req.channel, 0, MessageDeletedResponse {
+        channel: req.channel,
+        id: req.id,
+        deleter: name,
+        deleter_world: crate::util::id_from_world(world),
+    }).await?;
+
+    send(out, number, DeleteMessageResponse {
+        channel: req.channel,
+        id: req.id,
+    }).await
+}
@@ -0,0 +1,60 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{DIRECT_MESSAGES, DirectMessageReceivedResponse, DirectMessageRequest, DirectMessageResponse, ErrorCode, Requirement, ResponseKind};
+use crate::util::send;
+
+pub async fn direct_message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: DirectMessageRequest) -> Result<()> {
+    let (user, pk) = {
+        let client = client_state.read().await;
+        match &client.user {
+            Some(u) => (u.clone(), client.pk.clone()),
+            None => return Ok(()),
+        }
+    };
+
+    // someone who isn't taking direct messages looks the same as someone
+    // who isn't online
+    const NOT_ONLINE: &str = "user not online";
+    let target_id = match state.read().await.ids.get(&(req.name.clone(), req.world)) {
+        Some(id) => *id,
+        None => return send(out, number, ErrorResponse::new(None, ErrorCode::UserNotOnline, NOT_ONLINE)).await,
+    };
+
+    if target_id == user.lodestone_id {
+        return send(out, number, ErrorResponse::new(None, ErrorCode::CannotTargetSelf, "cannot message self")).await;
+    }
+
+    let target = state.read().await.clients.get(&target_id).cloned();
+    let target = match target {
+        Some(target) => target,
+        None => return send(out, number, ErrorResponse::new(None, ErrorCode::UserNotOnline, NOT_ONLINE)).await,
+    };
+
+    let target = target.read().await;
+    if !target.allow_direct_messages || !target.supports(Requirement::Capability(DIRECT_MESSAGES)) {
+        return send(out, number, ErrorResponse::new(None, ErrorCode::UserNotOnline, NOT_ONLINE)).await;
+    }
+
+    // gone or too far behind to take it, which is as good as offline
+    let queued = target.tx.push(ResponseContainer {
+        number: 0,
+        kind: ResponseKind::DirectMessageReceived(DirectMessageReceivedResponse {
+            name: user.name,
+            world: crate::util::id_from_world(user.world),
+            pk: pk.into(),
+            message: req.message,
+        }),
+    });
+    if !queued {
+        return send(out, number, ErrorResponse::new(None, ErrorCode::UserNotOnline, NOT_ONLINE)).await;
+    }
+
+    send(out, number, DirectMessageResponse {
+        name: req.name,
+        world: req.world,
+    }).await
+}
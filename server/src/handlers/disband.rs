@@ -3,14 +3,15 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, Rank, State, WsStream};
-use crate::types::protocol::{DisbandRequest, DisbandResponse};
+use crate::{ClientState, ErrorResponse, Outbound, Rank, State};
+use crate::types::protocol::{DisbandRequest, DisbandResponse, ErrorCode};
 use crate::util::send;
 
-pub async fn disband(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: DisbandRequest) -> Result<()> {
+pub async fn disband(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: DisbandRequest) -> Result<()> {
     match client_state.read().await.get_rank(req.channel, &state).await? {
         Some(Rank::Admin) => {}
-        _ => return send(conn, number, ErrorResponse::new(req.channel, "not in channel/not enough permissions")).await,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
     }
 
     crate::util::send_to_all(&state, req.channel, 0, DisbandResponse {
@@ -27,7 +28,7 @@ pub async fn disband(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
         .await
         .context("could not disband channel")?;
 
-    send(conn, number, DisbandResponse {
+    send(out, number, DisbandResponse {
         channel: req.channel,
     }).await
 }
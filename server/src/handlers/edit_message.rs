@@ -0,0 +1,42 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{EditMessageRequest, EditMessageResponse, ErrorCode, MessageEditedResponse};
+use crate::util::send;
+
+pub async fn edit_message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: EditMessageRequest) -> Result<()> {
+    let lodestone_id = match client_state.read().await.lodestone_id() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    if !client_state.read().await.in_channel(req.channel, &state).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    match crate::types::message::origin(&state, req.id).await? {
+        Some(origin) if origin.channel == req.channel => {
+            if origin.sender != lodestone_id {
+                return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "can only edit your own messages")).await;
+            }
+        }
+        _ => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::MessageNotFound, "no such message")).await,
+    }
+
+    let edited = crate::types::message::edit(&state, req.id, req.message.as_inner()).await?;
+
+    crate::util::send_to_members(&state, req.channel, 0, MessageEditedResponse {
+        channel: req.channel,
+        id: req.id,
+        message: req.message,
+        timestamp: crate::types::message::millis(edited),
+    }).await?;
+
+    send(out, number, EditMessageResponse {
+        channel: req.channel,
+        id: req.id,
+    }).await
+}
@@ -0,0 +1,30 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, HistoryRequest, HistoryResponse};
+use crate::util::send;
+
+/// The most messages returned in one page.
+const MAX_LIMIT: u32 = 100;
+
+pub async fn history(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: HistoryRequest) -> Result<()> {
+    if !client_state.read().await.in_channel(req.channel, &state).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    let retention = crate::types::message::retention(&state, req.channel).await?;
+    let oldest = crate::types::message::cutoff(retention);
+    let limit = req.limit.clamp(1, MAX_LIMIT);
+
+    let viewer = client_state.read().await.lodestone_id().unwrap_or_default();
+    let (messages, more) = crate::types::message::page(&state, req.channel, req.cursor, limit, oldest, viewer).await?;
+
+    send(out, number, HistoryResponse {
+        channel: req.channel,
+        messages,
+        more,
+    }).await
+}
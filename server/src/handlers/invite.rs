@@ -1,13 +1,14 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, ResponseContainer, State, WsStream};
-use crate::types::protocol::{InvitedResponse, InviteRequest, InviteResponse, MemberChangeKind, MemberChangeResponse, ResponseKind};
-use crate::types::protocol::channel::{Channel, Rank};
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{ErrorCode, InvitedResponse, InviteRequest, InviteResponse, MemberChangeKind, MemberChangeResponse, OFFLINE_INVITES, Requirement, ResponseKind};
+use crate::types::protocol::channel::Rank;
 
-pub async fn invite(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: InviteRequest) -> Result<()> {
+pub async fn invite(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: InviteRequest) -> Result<()> {
     let user = match &client_state.read().await.user {
         Some(u) => u.clone(),
         None => return Ok(()),
@@ -16,28 +17,40 @@ pub async fn invite(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
 
     let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
         Some(r) => r,
-        None => return crate::util::send(conn, number, ErrorResponse::new(req.channel, "not in channel")).await,
+        None => return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
     };
 
     if rank < Rank::Moderator {
-        return crate::util::send(conn, number, ErrorResponse::new(req.channel, "not enough permissions to invite")).await;
+        return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions to invite")).await;
     }
 
     const NOT_ONLINE: &str = "user not online";
-    let target_id = match state.read().await.ids.get(&(req.name.clone(), req.world)) {
-        Some(id) => *id,
-        None => return crate::util::send(conn, number, ErrorResponse::new(req.channel, NOT_ONLINE)).await,
+    let target_id = if req.invite_key {
+        // encrypted to the stored invite key, so they can be offline
+        match crate::types::user::find(&state, &req.name, req.world).await? {
+            Some(user) if user.invite_pk.is_some() => user.lodestone_id,
+            _ => return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotOnline, NOT_ONLINE)).await,
+        }
+    } else {
+        match state.read().await.ids.get(&(req.name.clone(), req.world)) {
+            Some(id) => *id,
+            None => return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotOnline, NOT_ONLINE)).await,
+        }
     };
     let target_id_i = target_id as i64;
 
     if let Some(client) = state.read().await.clients.get(&target_id) {
         if !client.read().await.allow_invites {
-            return crate::util::send(conn, number, ErrorResponse::new(req.channel, NOT_ONLINE)).await;
+            return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotOnline, NOT_ONLINE)).await;
         }
     }
 
     if target_id_i == lodestone_id {
-        return crate::util::send(conn, number, ErrorResponse::new(req.channel, "cannot invite self")).await;
+        return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::CannotTargetSelf, "cannot invite self")).await;
+    }
+
+    if crate::types::ban::is_banned(&state, req.channel, target_id).await? {
+        return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::Banned, "user is banned from channel")).await;
     }
 
     let channel_id = req.channel.as_simple().to_string();
@@ -53,22 +66,24 @@ pub async fn invite(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
         .context("could not query database for membership")?;
 
     if membership.count > 0 {
-        return crate::util::send(conn, number, ErrorResponse::new(req.channel, "already in channel")).await;
+        return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::AlreadyInChannel, "already in channel")).await;
     }
 
-    // check for existing invite
+    // check for existing invite. an expired one is replaced below
+    let now = Utc::now().naive_utc();
     let invite = sqlx::query!(
         // language=sqlite
-        "select count(*) as count from channel_invites where channel_id = ? and invited = ?",
+        "select count(*) as count from channel_invites where channel_id = ? and invited = ? and (expires_at is null or expires_at > ?)",
         channel_id,
         target_id_i,
+        now,
     )
         .fetch_one(&state.read().await.db)
         .await
         .context("could not query database for invite")?;
 
     if invite.count > 0 {
-        return crate::util::send(conn, number, ErrorResponse::new(req.channel, "already invited")).await;
+        return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::AlreadyInvited, "already invited")).await;
     }
 
     crate::util::send_to_all(&state, req.channel, 0, MemberChangeResponse {
@@ -81,46 +96,65 @@ pub async fn invite(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
         },
     }).await?;
 
+    // inviter's info
+    let pk = client_state.read().await.pk.clone();
+    let (name, world) = match &client_state.read().await.user {
+        Some(c) => (c.name.clone(), c.world),
+        None => return Ok(()),
+    };
+
+    // clamped so a silly expiry can't overflow
+    let expires_at = req.expires_in
+        .map(|secs| now + Duration::seconds(secs.min(1000 * 365 * 24 * 60 * 60) as i64));
+
+    // only a secret encrypted to the invite key is any use later
+    let (encrypted_secret, inviter_pk) = match req.invite_key {
+        true => (Some(req.encrypted_secret.as_inner().as_slice()), Some(pk.as_slice())),
+        false => (None, None),
+    };
     sqlx::query!(
         // language=sqlite
-        "insert into channel_invites (channel_id, invited, inviter) values (?, ?, ?)",
+        "insert or replace into channel_invites (channel_id, invited, inviter, encrypted_secret, inviter_pk, expires_at) values (?, ?, ?, ?, ?, ?)",
         channel_id,
         target_id_i,
         lodestone_id,
+        encrypted_secret,
+        inviter_pk,
+        expires_at,
     )
         .execute(&state.read().await.db)
         .await
         .context("could not add invite")?;
 
-    // inviter's info
-    let pk = client_state.read().await.pk.clone();
-    let (name, world) = match &client_state.read().await.user {
-        Some(c) => (c.name.clone(), c.world),
-        None => return Ok(()),
-    };
-
-    // send invite to invitee
-    match state.read().await.clients.get(&target_id) {
+    // send invite to invitee. invites to the invite key reach clients that
+    // can't read them, or that aren't online, when they next log in
+    let target = state.read().await.clients.get(&target_id).cloned();
+    match target {
         Some(c) => {
-            let channel = Channel::get(&state, req.channel)
-                .await
-                .context("could not get channel")?
-                .context("no such channel")?;
-            c.read().await.tx.send(ResponseContainer {
-                number: 0,
-                kind: ResponseKind::Invited(InvitedResponse {
-                    channel,
-                    name,
-                    world: crate::util::id_from_world(world),
-                    pk: pk.into(),
-                    encrypted_secret: req.encrypted_secret,
-                }),
-            }).await?;
+            let deliver = !req.invite_key || c.read().await.supports(Requirement::Capability(OFFLINE_INVITES));
+            if deliver {
+                let channel = crate::types::channel::get(&state, req.channel)
+                    .await
+                    .context("could not get channel")?
+                    .context("no such channel")?;
+                c.read().await.tx.push(ResponseContainer {
+                    number: 0,
+                    kind: ResponseKind::Invited(InvitedResponse {
+                        channel,
+                        name,
+                        world: crate::util::id_from_world(world),
+                        pk: pk.into(),
+                        encrypted_secret: req.encrypted_secret,
+                        invite_key: req.invite_key,
+                    }),
+                });
+            }
         }
-        None => return crate::util::send(conn, number, ErrorResponse::new(req.channel, NOT_ONLINE)).await,
+        None if req.invite_key => {}
+        None => return crate::util::send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotOnline, NOT_ONLINE)).await,
     }
 
-    crate::util::send(conn, number, InviteResponse {
+    crate::util::send(out, number, InviteResponse {
         channel: req.channel,
         name: req.name,
         world: req.world,
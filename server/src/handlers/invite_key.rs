@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use tokio::sync::RwLock;
+
+use crate::{ClientState, Outbound, State, util};
+use crate::types::protocol::{InviteKeyRequest, InviteKeyResponse};
+
+pub async fn invite_key(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: InviteKeyRequest) -> Result<()> {
+    let lodestone_id = match client_state.read().await.lodestone_id() {
+        Some(id) => id as i64,
+        None => return Ok(()),
+    };
+
+    let pk = req.pk.map(|pk| pk.into_inner());
+    let mut tx = state.read().await.db.begin().await.context("could not start transaction")?;
+
+    let old = sqlx::query!(
+        // language=sqlite
+        "select invite_pk from users where lodestone_id = ?",
+        lodestone_id,
+    )
+        .fetch_one(&mut *tx)
+        .await
+        .context("could not get invite key")?;
+
+    // nothing encrypted to the old key can be read any more
+    if old.invite_pk != pk {
+        sqlx::query!(
+            // language=sqlite
+            "delete from channel_invites where invited = ? and encrypted_secret is not null",
+            lodestone_id,
+        )
+            .execute(&mut *tx)
+            .await
+            .context("could not remove invites")?;
+    }
+
+    sqlx::query!(
+        // language=sqlite
+        "update users set invite_pk = ? where lodestone_id = ?",
+        pk,
+        lodestone_id,
+    )
+        .execute(&mut *tx)
+        .await
+        .context("could not set invite key")?;
+
+    tx.commit().await.context("could not commit invite key")?;
+
+    util::send(out, number, InviteKeyResponse {
+        set: pk.is_some(),
+    }).await
+}
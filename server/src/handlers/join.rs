@@ -1,14 +1,15 @@
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, State, WsStream};
-use crate::types::protocol::{JoinRequest, JoinResponse, MemberChangeKind, MemberChangeResponse};
-use crate::types::protocol::channel::{Channel, Rank};
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, JoinRequest, JoinResponse, MemberChangeKind, MemberChangeResponse};
+use crate::types::protocol::channel::Rank;
 use crate::util::send;
 
-pub async fn join(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: JoinRequest) -> Result<()> {
+pub async fn join(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: JoinRequest) -> Result<()> {
     let user = match &client_state.read().await.user {
         Some(user) => user.clone(),
         None => return Ok(()),
@@ -16,18 +17,20 @@ pub async fn join(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
     let lodestone_id = user.lodestone_id as i64;
 
     let channel_id = req.channel.as_simple().to_string();
+    let now = Utc::now().naive_utc();
     let invite = sqlx::query!(
         // language=sqlite
-        "delete from channel_invites where channel_id = ? and invited = ? returning *",
+        "delete from channel_invites where channel_id = ? and invited = ? and (expires_at is null or expires_at > ?) returning *",
         channel_id,
         lodestone_id,
+        now,
     )
         .fetch_optional(&state.read().await.db)
         .await
         .context("failed to fetch invite")?;
 
     if invite.is_none() {
-        return send(conn, number, ErrorResponse::new(req.channel, "you were not invited to that channel")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInvited, "you were not invited to that channel")).await;
     }
 
     crate::util::send_to_all(&state, req.channel, 0, MemberChangeResponse {
@@ -37,24 +40,115 @@ pub async fn join(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
         kind: MemberChangeKind::Join,
     }).await?;
 
+    // everything sent before joining counts as read
     let rank = Rank::Member.as_u8();
     sqlx::query!(
         // language=sqlite
-        "insert into user_channels (lodestone_id, channel_id, rank) values (?, ?, ?)",
+        "insert into user_channels (lodestone_id, channel_id, rank, last_read) select ?, ?, ?, last_sequence from channels where id = ?",
         lodestone_id,
         channel_id,
         rank,
+        channel_id,
     )
         .execute(&state.read().await.db)
         .await
         .context("failed to add user to channel")?;
 
-    let channel = Channel::get(&state, req.channel)
+    let channel = crate::types::channel::get(&state, req.channel)
         .await
         .context("failed to get channel")?
         .context("no such channel")?;
 
-    send(conn, number, JoinResponse {
+    send(out, number, JoinResponse {
         channel,
     }).await
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::sync::Arc;
+    use std::sync::atomic::AtomicU64;
+
+    use chrono::{Duration, Utc};
+    use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+    use sqlx::sqlite::SqlitePoolOptions;
+    use tokio::sync::RwLock;
+    use uuid::Uuid;
+
+    use crate::{ClientState, State};
+    use crate::types::config::Config;
+    use crate::types::protocol::{ErrorCode, JoinRequest, MIN_VERSION, ResponseKind};
+    use crate::types::user::User;
+
+    #[tokio::test]
+    async fn expired_invite_is_not_accepted() {
+        // one connection, since every connection to :memory: is its own database
+        let db = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        crate::MIGRATOR.run(&db).await.unwrap();
+
+        let channel = Uuid::new_v4();
+        let channel_id = channel.as_simple().to_string();
+        for (id, name) in [(1_i64, "Invited Person"), (2, "Inviting Person")] {
+            sqlx::query("insert into users (lodestone_id, name, world, key_short, key_hash) values (?, ?, 'Adamantoise', '', '')")
+                .bind(id)
+                .bind(name)
+                .execute(&db)
+                .await
+                .unwrap();
+        }
+        sqlx::query("insert into channels (id, name) values (?, x'00')")
+            .bind(&channel_id)
+            .execute(&db)
+            .await
+            .unwrap();
+        sqlx::query("insert into channel_invites (channel_id, invited, inviter, expires_at) values (?, 1, 2, ?)")
+            .bind(&channel_id)
+            .bind(Utc::now().naive_utc() - Duration::minutes(1))
+            .execute(&db)
+            .await
+            .unwrap();
+
+        let config: Arc<Config> = Arc::new(toml::from_str(include_str!("../../config.example.toml")).unwrap());
+        let (out, rx) = crate::outbound::channel(&config.server.outbound);
+        let state = Arc::new(RwLock::new(State {
+            db,
+            clients: Default::default(),
+            ids: Default::default(),
+            secrets_requests: Default::default(),
+            typing: Default::default(),
+            messages_sent: AtomicU64::default(),
+            decode_failures: AtomicU64::default(),
+            pushes_dropped: AtomicU64::default(),
+            overflow_disconnects: AtomicU64::default(),
+            updater_tx: tokio::sync::mpsc::unbounded_channel().0,
+            config,
+        }));
+        let client_state = Arc::new(RwLock::new(ClientState {
+            user: Some(User {
+                lodestone_id: 1,
+                name: "Invited Person".to_string(),
+                world: World::from_str("Adamantoise").unwrap(),
+                hash: String::new(),
+            }),
+            tx: out.clone(),
+            shutdown_tx: tokio::sync::mpsc::channel(1).0,
+            pk: Default::default(),
+            allow_invites: true,
+            allow_direct_messages: false,
+            version: MIN_VERSION,
+            capabilities: Default::default(),
+        }));
+
+        super::join(state, client_state, &out, 1, JoinRequest { channel }).await.unwrap();
+
+        match rx.try_recv().map(|msg| msg.container.kind) {
+            Some(ResponseKind::Error(error)) => assert_eq!(error.code, ErrorCode::NotInvited),
+            kind => panic!("unexpected response {kind:?}"),
+        }
+    }
+}
@@ -3,12 +3,12 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, State, WsStream};
-use crate::types::protocol::{KickRequest, KickResponse, MemberChangeKind, MemberChangeResponse};
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, KickRequest, KickResponse, MemberChangeKind, MemberChangeResponse};
 use crate::types::protocol::channel::Rank;
 use crate::util::send;
 
-pub async fn kick(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: KickRequest) -> Result<()> {
+pub async fn kick(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: KickRequest) -> Result<()> {
     let user = match &client_state.read().await.user {
         Some(user) => user.clone(),
         None => return Ok(()),
@@ -16,12 +16,13 @@ pub async fn kick(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
 
     let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
         Some(rank) if rank >= Rank::Moderator => rank,
-        _ => return send(conn, number, ErrorResponse::new(req.channel, "not in channel/not enough permissions")).await,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
     };
 
     let target_id = match state.read().await.get_id(&state, &req.name, req.world).await {
         Some(id) => id,
-        None => return send(conn, number, ErrorResponse::new(req.channel, "user not found")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotFound, "user not found")).await,
     };
     let target_id_i = target_id as i64;
 
@@ -39,10 +40,10 @@ pub async fn kick(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
 
     match target_rank {
         Some(target) if target >= rank => {
-            return send(conn, number, ErrorResponse::new(req.channel, "cannot kick someone of equal or higher rank")).await;
+            return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "cannot kick someone of equal or higher rank")).await;
         }
         None if !crate::util::is_invited(&state, req.channel, target_id).await? => {
-            return send(conn, number, ErrorResponse::new(req.channel, "user not in channel")).await;
+            return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotInChannel, "user not in channel")).await;
         }
         _ => {}
     }
@@ -90,7 +91,7 @@ pub async fn kick(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
             .context("could not kick user")?;
     }
 
-    send(conn, number, KickResponse {
+    send(out, number, KickResponse {
         channel: req.channel,
         name: req.name.clone(),
         world: req.world,
@@ -3,13 +3,13 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, Rank, State, types::protocol::{
+use crate::{ClientState, ErrorResponse, Outbound, Rank, State, types::protocol::{
     LeaveRequest,
     LeaveResponse,
-}, util::send, WsStream};
-use crate::types::protocol::{MemberChangeKind, MemberChangeResponse};
+}, util::send};
+use crate::types::protocol::{ErrorCode, MemberChangeKind, MemberChangeResponse};
 
-pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: LeaveRequest) -> Result<()> {
+pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: LeaveRequest) -> Result<()> {
     let user = match &client_state.read().await.user {
         Some(user) => user.clone(),
         None => return Ok(()),
@@ -34,7 +34,7 @@ pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSta
             if is_invited {
                 Rank::Invited
             } else {
-                return send(conn, number, ErrorResponse::new(req.channel, "not in that channel")).await;
+                return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in that channel")).await;
             }
         }
     };
@@ -54,7 +54,7 @@ pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSta
     // if the leaving user is an admin and there's more than one user,
     // the admin must promote someone before they can leave
     if users > 1 && rank == Rank::Admin {
-        return send(conn, number, LeaveResponse::error(req.channel, "you must promote someone to admin before leaving")).await;
+        return send(out, number, LeaveResponse::error(req.channel, ErrorCode::PromoteBeforeLeaving, "you must promote someone to admin before leaving")).await;
     }
 
     // if there's only one user and this isn't an invite decline, we can
@@ -69,7 +69,7 @@ pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSta
             .await
             .context("failed to delete channel")?;
 
-        return send(conn, number, LeaveResponse::success(req.channel)).await;
+        return send(out, number, LeaveResponse::success(req.channel)).await;
     }
 
     let kind = if is_decline {
@@ -105,5 +105,5 @@ pub async fn leave(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSta
         kind,
     }).await?;
 
-    send(conn, number, LeaveResponse::success(req.channel)).await
+    send(out, number, LeaveResponse::success(req.channel)).await
 }
@@ -1,23 +1,26 @@
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
+use chrono::Utc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::{ClientState, State, types::protocol::{
+use crate::{ClientState, ErrorResponse, Outbound, State, types::protocol::{
     channel::{
         Channel,
         ChannelMember,
         Rank,
         SimpleChannel,
     },
+    ErrorCode,
     ListRequest,
     ListResponse,
-}, util::send, World, WsStream};
+}, util::send, World};
 use crate::util::RawMember;
 
-pub async fn list(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: ListRequest) -> Result<()> {
+pub async fn list(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: ListRequest) -> Result<()> {
     let lodestone_id = match &client_state.read().await.user {
         Some(u) => u.lodestone_id,
         None => return Ok(()),
@@ -34,9 +37,33 @@ pub async fn list(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientStat
             members: get_members(lodestone_id, &state, id).await?,
         },
         ListRequest::Invites => ListResponse::Invites(get_invites(lodestone_id, &state).await?),
+        ListRequest::PendingInvites(id) => {
+            match client_state.read().await.get_rank(id, &state).await? {
+                Some(rank) if rank >= Rank::Moderator => {}
+                Some(_) => return send(out, number, ErrorResponse::new(id, ErrorCode::InsufficientRank, "not enough permissions to list invites")).await,
+                None => return send(out, number, ErrorResponse::new(id, ErrorCode::NotInChannel, "not in channel")).await,
+            }
+
+            ListResponse::PendingInvites {
+                id,
+                invites: crate::types::channel::get_channel_invites(&state, id).await?,
+            }
+        }
+        ListRequest::Bans(id) => {
+            match client_state.read().await.get_rank(id, &state).await? {
+                Some(rank) if rank >= Rank::Moderator => {}
+                Some(_) => return send(out, number, ErrorResponse::new(id, ErrorCode::InsufficientRank, "not enough permissions to list bans")).await,
+                None => return send(out, number, ErrorResponse::new(id, ErrorCode::NotInChannel, "not in channel")).await,
+            }
+
+            ListResponse::Bans {
+                id,
+                bans: crate::types::ban::get_all(&state, id).await?,
+            }
+        }
     };
 
-    send(conn, number, resp).await
+    send(out, number, resp).await
 }
 
 async fn ids_to_channels(ids: &[&str], state: &RwLock<State>) -> Vec<Channel> {
@@ -47,7 +74,7 @@ async fn ids_to_channels(ids: &[&str], state: &RwLock<State>) -> Vec<Channel> {
             Err(_) => continue,
         };
 
-        let channel = match Channel::get(state, id).await {
+        let channel = match crate::types::channel::get(state, id).await {
             Ok(Some(channel)) => channel,
             _ => continue,
         };
@@ -62,7 +89,7 @@ async fn get_full_channels(lodestone_id: u64, state: &RwLock<State>) -> Result<V
     let lodestone_id_i = lodestone_id as i64;
     let channel_ids = sqlx::query!(
         // language=sqlite
-        "select channel_id from user_channels where lodestone_id = ?",
+        "select user_channels.channel_id, user_channels.last_read, channels.last_sequence from user_channels inner join channels on channels.id = user_channels.channel_id where user_channels.lodestone_id = ?",
         lodestone_id_i,
     )
         .fetch_all(&state.read().await.db)
@@ -73,15 +100,31 @@ async fn get_full_channels(lodestone_id: u64, state: &RwLock<State>) -> Result<V
         .iter()
         .map(|id| id.channel_id.as_str())
         .collect();
-    Ok(ids_to_channels(&ids, state).await)
+    let mut channels = ids_to_channels(&ids, state).await;
+
+    let markers: HashMap<&str, (i64, i64)> = channel_ids
+        .iter()
+        .map(|c| (c.channel_id.as_str(), (c.last_read, c.last_sequence)))
+        .collect();
+    for channel in &mut channels {
+        let id = channel.id.as_simple().to_string();
+        if let Some(&(last_read, last_sequence)) = markers.get(id.as_str()) {
+            channel.last_read = last_read.max(0) as u64;
+            channel.unread = (last_sequence - last_read).max(0) as u64;
+        }
+    }
+
+    Ok(channels)
 }
 
 async fn get_full_invites(lodestone_id: u64, state: &RwLock<State>) -> Result<Vec<Channel>> {
     let lodestone_id_i = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
     let channel_ids = sqlx::query!(
         // language=sqlite
-        "select channel_id from channel_invites where invited = ?",
+        "select channel_id from channel_invites where invited = ? and (expires_at is null or expires_at > ?)",
         lodestone_id_i,
+        now,
     )
         .fetch_all(&state.read().await.db)
         .await
@@ -95,13 +138,14 @@ async fn get_full_invites(lodestone_id: u64, state: &RwLock<State>) -> Result<Ve
 }
 
 async fn get_channels(lodestone_id: u64, state: &RwLock<State>) -> Result<Vec<SimpleChannel>> {
-    SimpleChannel::get_all_for_user(state, lodestone_id)
+    crate::types::channel::get_all_for_user(state, lodestone_id)
         .await
         .context("could not get channels for user")
 }
 
 async fn get_members(lodestone_id: u64, state: &RwLock<State>, channel_id: Uuid) -> Result<Vec<ChannelMember>> {
     let lodestone_id_i = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
 
     let channel_id_str = channel_id.as_simple().to_string();
     let users: Vec<RawMember> = sqlx::query_as!(
@@ -117,13 +161,16 @@ async fn get_members(lodestone_id: u64, state: &RwLock<State>, channel_id: Uuid)
     let invited: Vec<RawMember> = sqlx::query_as!(
         RawMember,
         // language=sqlite
-        "select users.lodestone_id, users.name, users.world, cast(0 as int) as rank from channel_invites inner join users on users.lodestone_id = channel_invites.invited where channel_invites.channel_id = ?",
+        "select users.lodestone_id, users.name, users.world, cast(0 as int) as rank from channel_invites inner join users on users.lodestone_id = channel_invites.invited where channel_invites.channel_id = ? and (channel_invites.expires_at is null or channel_invites.expires_at > ?)",
         channel_id_str,
+        now,
     )
         .fetch_all(&state.read().await.db)
         .await
         .context("failed to get invited members")?;
 
+    let mutes = crate::types::mute::get_all(state, channel_id).await?;
+
     let mut found = false;
     let mut members = Vec::with_capacity(users.len());
     for user in users.into_iter().chain(invited.into_iter()) {
@@ -142,6 +189,7 @@ async fn get_members(lodestone_id: u64, state: &RwLock<State>, channel_id: Uuid)
             world: crate::util::id_from_world(world),
             rank: Rank::from_u8(user.rank as u8),
             online,
+            muted_until: mutes.get(&(user.lodestone_id as u64)).copied(),
         });
     }
 
@@ -153,7 +201,7 @@ async fn get_members(lodestone_id: u64, state: &RwLock<State>, channel_id: Uuid)
 }
 
 async fn get_invites(lodestone_id: u64, state: &RwLock<State>) -> Result<Vec<SimpleChannel>> {
-    SimpleChannel::get_invites_for_user(state, lodestone_id)
+    crate::types::channel::get_invites_for_user(state, lodestone_id)
         .await
         .context("could not get channels for user")
 }
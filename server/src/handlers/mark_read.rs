@@ -0,0 +1,28 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, MarkReadRequest, MarkReadResponse};
+use crate::util::send;
+
+pub async fn mark_read(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: MarkReadRequest) -> Result<()> {
+    let lodestone_id = match client_state.read().await.lodestone_id() {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+
+    if !client_state.read().await.in_channel(req.channel, &state).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    // the marker lives on the server, so the next session picks it up from
+    // the channel list
+    let sequence = crate::types::channel::mark_read(&state, req.channel, lodestone_id, req.sequence).await?;
+
+    send(out, number, MarkReadResponse {
+        channel: req.channel,
+        sequence,
+    }).await
+}
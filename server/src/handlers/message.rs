@@ -4,11 +4,11 @@ use std::sync::atomic::Ordering;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, MessageRequest, MessageResponse, ResponseContainer, State, util, WsStream};
-use crate::types::protocol::ResponseKind;
+use crate::{ClientState, ErrorResponse, MessageRequest, MessageResponse, Outbound, ResponseContainer, State, util};
+use crate::types::protocol::{ErrorCode, MessageAckResponse, ResponseKind};
 use crate::util::send;
 
-pub async fn message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: MessageRequest) -> Result<()> {
+pub async fn message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: MessageRequest) -> Result<()> {
     let (lodestone_id, sender, world) = match &client_state.read().await.user {
         Some(u) => (u.lodestone_id, u.name.clone(), u.world),
         None => return Ok(()),
@@ -28,11 +28,28 @@ pub async fn message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
         .iter()
         .any(|m| m.lodestone_id as u64 == lodestone_id);
     if !in_channel {
-        return send(conn, number, ErrorResponse::new(req.channel, "not in channel")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    if crate::types::mute::muted_until(&state, req.channel, lodestone_id).await?.is_some() {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::Muted, "you are muted in this channel")).await;
+    }
+
+    // replies have to be to a message in the same channel
+    if let Some(reply_to) = req.reply_to {
+        match crate::types::message::origin(&state, reply_to).await? {
+            Some(origin) if origin.channel == req.channel => {}
+            _ => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::MessageNotFound, "replied-to message not found")).await,
+        }
     }
 
     state.read().await.messages_sent.fetch_add(1, Ordering::SeqCst);
 
+    let stamp = crate::types::message::record(&state, req.channel, lodestone_id, req.message.as_inner(), req.reply_to).await?;
+
+    // only held for anyone who isn't online if the channel keeps messages
+    let keep = crate::types::message::retention(&state, req.channel).await? > 0;
+
     let resp = ResponseContainer {
         number: 0,
         kind: ResponseKind::Message(MessageResponse {
@@ -40,17 +57,45 @@ pub async fn message(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
             sender,
             world: util::id_from_world(world),
             message: req.message,
+            id: stamp.id as u64,
+            timestamp: crate::types::message::millis(stamp.received),
+            sequence: stamp.sequence as u64,
+            reply_to: req.reply_to,
         }),
     };
 
+    // you've read your own message
+    crate::types::channel::mark_read(&state, req.channel, lodestone_id, stamp.sequence as u64).await?;
+
+    // how many other members got it now and how many will get it later
+    let (mut live, mut queued) = (0, 0);
     for member in members {
-        let client = match state.read().await.clients.get(&(member.lodestone_id as u64)).cloned() {
+        let is_sender = member.lodestone_id as u64 == lodestone_id;
+        let client = state.read().await.clients.get(&(member.lodestone_id as u64)).cloned();
+        let client = match client {
             Some(c) => c,
-            None => continue,
+            None => {
+                if keep {
+                    crate::types::message::queue_for(&state, stamp.id, member.lodestone_id as u64).await?;
+                    queued += 1;
+                }
+
+                continue;
+            }
         };
 
-        client.read().await.tx.send(resp.clone()).await.ok();
+        client.read().await.tx.push(resp.clone());
+        if !is_sender {
+            live += 1;
+        }
     }
 
-    Ok(())
+    // only sent to clients that negotiated acks
+    send(out, number, MessageAckResponse {
+        channel: req.channel,
+        id: stamp.id as u64,
+        sequence: stamp.sequence as u64,
+        live,
+        queued,
+    }).await
 }
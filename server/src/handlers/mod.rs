@@ -1,42 +1,66 @@
 pub use self::{
+    allow_direct_messages::*,
     allow_invites::*,
     authenticate::*,
+    ban::*,
     create::*,
     delete_account::*,
+    delete_message::*,
+    direct_message::*,
     disband::*,
+    edit_message::*,
+    history::*,
     invite::*,
+    invite_key::*,
     join::*,
     kick::*,
     leave::*,
     list::*,
+    mark_read::*,
     message::*,
+    mute::*,
     ping::*,
     promote::*,
     public_key::*,
+    react::*,
     register::*,
     secrets::*,
     send_secrets::*,
+    typing::*,
+    unban::*,
     update::*,
     version::*,
 };
 
+pub mod allow_direct_messages;
 pub mod allow_invites;
 pub mod authenticate;
+pub mod ban;
 pub mod create;
 pub mod delete_account;
+pub mod delete_message;
+pub mod direct_message;
 pub mod disband;
+pub mod edit_message;
+pub mod history;
 pub mod invite;
+pub mod invite_key;
 pub mod join;
 pub mod kick;
 pub mod leave;
 pub mod list;
+pub mod mark_read;
 pub mod message;
+pub mod mute;
 pub mod ping;
 pub mod promote;
 pub mod public_key;
+pub mod react;
 pub mod register;
 pub mod secrets;
 pub mod send_secrets;
+pub mod typing;
+pub mod unban;
 pub mod update;
 pub mod version;
 
@@ -0,0 +1,98 @@
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use chrono::{Duration, Utc};
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, MemberChangeKind, MemberChangeResponse, MuteRequest, MuteResponse};
+use crate::types::protocol::channel::Rank;
+use crate::util::send;
+
+pub async fn mute(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: MuteRequest) -> Result<()> {
+    let user = match &client_state.read().await.user {
+        Some(user) => user.clone(),
+        None => return Ok(()),
+    };
+
+    let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
+        Some(rank) if rank >= Rank::Moderator => rank,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
+    };
+
+    let target_id = match state.read().await.get_id(&state, &req.name, req.world).await {
+        Some(id) => id,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotFound, "user not found")).await,
+    };
+    let target_id_i = target_id as i64;
+
+    if target_id == user.lodestone_id {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::CannotTargetSelf, "cannot mute self")).await;
+    }
+
+    let channel_id_str = req.channel.as_simple().to_string();
+    let target_rank: Option<Rank> = sqlx::query!(
+        // language=sqlite
+        "select rank from user_channels where channel_id = ? and lodestone_id = ?",
+        channel_id_str,
+        target_id_i,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not query database for rank")?
+        .map(|row| Rank::from_u8(row.rank as u8));
+
+    match target_rank {
+        Some(target) if target >= rank => {
+            return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "cannot mute someone of equal or higher rank")).await;
+        }
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotInChannel, "user not in channel")).await,
+        _ => {}
+    }
+
+    if req.duration == 0 {
+        if crate::types::mute::muted_until(&state, req.channel, target_id).await?.is_none() {
+            return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotMuted, "user is not muted")).await;
+        }
+
+        crate::types::mute::set(&state, req.channel, target_id, None).await?;
+        crate::util::send_to_all(&state, req.channel, 0, MemberChangeResponse {
+            channel: req.channel,
+            name: req.name.clone(),
+            world: req.world,
+            kind: MemberChangeKind::Unmute,
+        }).await?;
+
+        return send(out, number, MuteResponse {
+            channel: req.channel,
+            name: req.name,
+            world: req.world,
+            muted_until: None,
+        }).await;
+    }
+
+    // clamped so a silly duration can't overflow
+    let duration = Duration::seconds(req.duration.min(1000 * 365 * 24 * 60 * 60) as i64);
+    let until = Utc::now().naive_utc() + duration;
+    crate::types::mute::set(&state, req.channel, target_id, Some(until)).await?;
+
+    let until_millis = crate::types::message::millis(until);
+    crate::util::send_to_all(&state, req.channel, 0, MemberChangeResponse {
+        channel: req.channel,
+        name: req.name.clone(),
+        world: req.world,
+        kind: MemberChangeKind::Mute {
+            muter: user.name,
+            muter_world: crate::util::id_from_world(user.world),
+            until: until_millis,
+        },
+    }).await?;
+
+    send(out, number, MuteResponse {
+        channel: req.channel,
+        name: req.name,
+        world: req.world,
+        muted_until: Some(until_millis),
+    }).await
+}
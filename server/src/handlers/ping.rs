@@ -1,8 +1,8 @@
 use anyhow::Result;
 
-use crate::WsStream;
+use crate::Outbound;
 use crate::types::protocol::PingResponse;
 
-pub async fn ping(conn: &mut WsStream, number: u32) -> Result<()> {
-    crate::util::send(conn, number, PingResponse {}).await
+pub async fn ping(out: &Outbound, number: u32) -> Result<()> {
+    crate::util::send(out, number, PingResponse {}).await
 }
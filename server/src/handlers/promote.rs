@@ -3,13 +3,13 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, State, WsStream};
-use crate::types::protocol::{MemberChangeResponse, PromoteRequest, PromoteResponse};
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, MemberChangeResponse, PromoteRequest, PromoteResponse};
 use crate::types::protocol::channel::Rank;
 use crate::types::protocol::MemberChangeKind;
 use crate::util::send;
 
-pub async fn promote(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: PromoteRequest) -> Result<()> {
+pub async fn promote(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: PromoteRequest) -> Result<()> {
     let user = match &client_state.read().await.user {
         Some(user) => user.clone(),
         None => return Ok(()),
@@ -19,21 +19,22 @@ pub async fn promote(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
 
     let rank = match client_state.read().await.get_rank(req.channel, &state).await? {
         Some(rank) if rank == Rank::Admin => rank,
-        _ => return send(conn, number, ErrorResponse::new(req.channel, "not in channel/not enough permissions")).await,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
     };
 
     if req.rank == Rank::Invited {
-        return send(conn, number, ErrorResponse::new(req.channel, "cannot change rank to invited")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InvalidRank, "cannot change rank to invited")).await;
     }
 
     let target_id = match state.read().await.get_id(&state, &req.name, req.world).await {
         Some(id) => id,
-        None => return send(conn, number, ErrorResponse::new(req.channel, "user not found")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotFound, "user not found")).await,
     };
     let target_id_i = target_id as i64;
 
     if target_id == lodestone_id {
-        return send(conn, number, ErrorResponse::new(req.channel, "cannot change own rank")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::CannotTargetSelf, "cannot change own rank")).await;
     }
 
     let channel_id_str = req.channel.as_simple().to_string();
@@ -49,9 +50,9 @@ pub async fn promote(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
 
     match target_rank {
         Some(target) if target.rank >= rank.as_u8() as i64 => {
-            return send(conn, number, ErrorResponse::new(req.channel, "cannot change rank of someone of equal or higher rank")).await;
+            return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "cannot change rank of someone of equal or higher rank")).await;
         }
-        None => return send(conn, number, ErrorResponse::new(req.channel, "user not in channel")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotInChannel, "user not in channel")).await,
         _ => {}
     }
 
@@ -103,7 +104,7 @@ pub async fn promote(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
         },
     }).await?;
 
-    send(conn, number, PromoteResponse {
+    send(out, number, PromoteResponse {
         channel: req.channel,
         name: req.name,
         world: req.world,
@@ -3,27 +3,48 @@ use std::sync::Arc;
 use anyhow::Result;
 use tokio::sync::RwLock;
 
-use crate::{State, WsStream};
-use crate::types::protocol::{PublicKeyRequest, PublicKeyResponse};
-use crate::util::redacted::Redacted;
+use crate::{Outbound, State};
+use crate::types::protocol::{DIRECT_MESSAGES, PublicKeyRequest, PublicKeyResponse, Requirement};
+use crate::types::protocol::redacted::Redacted;
 
-pub async fn public_key(state: Arc<RwLock<State>>, conn: &mut WsStream, number: u32, req: PublicKeyRequest) -> Result<()> {
+pub async fn public_key(state: Arc<RwLock<State>>, out: &Outbound, number: u32, req: PublicKeyRequest) -> Result<()> {
+    // the invite key is stored, so offline characters are looked up too
+    let registered = crate::types::user::find(&state, &req.name, req.world).await?;
     let id = match state.read().await.ids.get(&(req.name.clone(), req.world)) {
-        Some(id) => *id,
-        None => return crate::util::send(conn, number, PublicKeyResponse {
+        Some(id) => Some(*id),
+        None => registered.as_ref().map(|user| user.lodestone_id),
+    };
+    let id = match id {
+        Some(id) => id,
+        None => return crate::util::send(out, number, PublicKeyResponse {
             name: req.name,
             world: req.world,
             pk: None,
+            invite_pk: None,
         }).await,
     };
 
-    let pk = match state.read().await.clients.get(&id) {
-        Some(client) if client.read().await.allow_invites => Some(client.read().await.pk.clone()),
-        _ => None,
+    // the key is used both for invites and for direct messages
+    let client = state.read().await.clients.get(&id).cloned();
+    let (pk, allow_invites) = match client {
+        Some(client) => {
+            let client = client.read().await;
+            let allow_direct_messages = client.allow_direct_messages && client.supports(Requirement::Capability(DIRECT_MESSAGES));
+            ((client.allow_invites || allow_direct_messages).then(|| client.pk.clone()), client.allow_invites)
+        }
+        None => (None, true),
     };
-    crate::util::send(conn, number, PublicKeyResponse {
+
+    // someone online who isn't taking invites isn't taking them offline
+    // either
+    let invite_pk = registered
+        .filter(|user| user.lodestone_id == id && allow_invites)
+        .and_then(|user| user.invite_pk);
+
+    crate::util::send(out, number, PublicKeyResponse {
         name: req.name,
         world: req.world,
         pk: pk.map(Redacted::new),
+        invite_pk: invite_pk.map(Redacted::new),
     }).await
 }
@@ -0,0 +1,54 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, ReactionChangedResponse, ReactRequest, ReactResponse};
+use crate::util::send;
+
+/// Largest encrypted reaction accepted, in bytes.
+const MAX_REACTION_SIZE: usize = 256;
+
+pub async fn react(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: ReactRequest) -> Result<()> {
+    let (lodestone_id, name, world) = match &client_state.read().await.user {
+        Some(u) => (u.lodestone_id, u.name.clone(), u.world),
+        None => return Ok(()),
+    };
+
+    if req.reaction.is_empty() || req.reaction.len() > MAX_REACTION_SIZE {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InvalidRequest, "invalid reaction")).await;
+    }
+
+    if !client_state.read().await.in_channel(req.channel, &state).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    match crate::types::message::origin(&state, req.id).await? {
+        Some(origin) if origin.channel == req.channel => {}
+        _ => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::MessageNotFound, "no such message")).await,
+    }
+
+    let changed = if req.remove {
+        crate::types::message::remove_reaction(&state, req.id, lodestone_id, req.reaction.as_inner()).await?
+    } else {
+        crate::types::message::add_reaction(&state, req.id, lodestone_id, req.reaction.as_inner()).await?
+    };
+
+    // nothing to tell anyone if it was already (or never) there
+    if changed {
+        crate::util::send_to_members(&state, req.channel, 0, ReactionChangedResponse {
+            channel: req.channel,
+            id: req.id,
+            reaction: req.reaction,
+            name,
+            world: crate::util::id_from_world(world),
+            removed: req.remove,
+        }).await?;
+    }
+
+    send(out, number, ReactResponse {
+        channel: req.channel,
+        id: req.id,
+    }).await
+}
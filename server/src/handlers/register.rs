@@ -6,9 +6,9 @@ use lodestone_scraper::LodestoneScraper;
 use rand::RngCore;
 use tokio::sync::RwLock;
 
-use crate::{ClientState, RegisterRequest, RegisterResponse, State, util::{hash_key, send, world_from_id}, WsStream};
+use crate::{ClientState, RegisterRequest, RegisterResponse, Outbound, State, util::{hash_key, send, world_from_id}};
 
-pub async fn register(state: Arc<RwLock<State>>, _client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: RegisterRequest) -> Result<()> {
+pub async fn register(state: Arc<RwLock<State>>, _client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: RegisterRequest) -> Result<()> {
     let scraper = LodestoneScraper::default();
 
     let world = world_from_id(req.world)
@@ -89,7 +89,7 @@ pub async fn register(state: Arc<RwLock<State>>, _client_state: Arc<RwLock<Clien
             None => unreachable!(),
         };
 
-        send(conn, number, RegisterResponse::Challenge {
+        send(out, number, RegisterResponse::Challenge {
             challenge,
         }).await?;
         return Ok(());
@@ -108,7 +108,7 @@ pub async fn register(state: Arc<RwLock<State>>, _client_state: Arc<RwLock<Clien
     let verified = chara_info.profile_text.contains(&challenge.challenge);
 
     if !verified {
-        send(conn, number, RegisterResponse::Failure).await?;
+        send(out, number, RegisterResponse::Failure).await?;
         return Ok(());
     }
 
@@ -147,7 +147,7 @@ pub async fn register(state: Arc<RwLock<State>>, _client_state: Arc<RwLock<Clien
         .await
         .context("could not insert user")?;
 
-    send(conn, number, RegisterResponse::Success {
+    send(out, number, RegisterResponse::Success {
         key: key.to_string().into(),
     }).await?;
 
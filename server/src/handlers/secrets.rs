@@ -5,8 +5,8 @@ use rand::seq::SliceRandom;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
-use crate::{ClientState, ErrorResponse, ResponseContainer, State, WsStream};
-use crate::types::protocol::{ResponseKind, SecretsRequest, SendSecretsResponse};
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{ErrorCode, ResponseKind, SecretsRequest, SendSecretsResponse};
 use crate::util::send;
 
 #[derive(Clone)]
@@ -16,9 +16,9 @@ pub struct SecretsRequestInfo {
     pub number: u32,
 }
 
-pub async fn secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: SecretsRequest) -> Result<()> {
+pub async fn secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: SecretsRequest) -> Result<()> {
     if client_state.read().await.get_rank_invite(req.channel, &state).await?.is_none() {
-        return send(conn, number, ErrorResponse::new(req.channel, "not in that channel")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in that channel")).await;
     }
 
     let lodestone_id = match client_state.read().await.lodestone_id() {
@@ -38,7 +38,7 @@ pub async fn secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
     }
 
     if members.is_empty() {
-        return send(conn, number, ErrorResponse::new(req.channel, "no other online members")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NoOnlineMembers, "no other online members")).await;
     }
 
     // because I am lazy
@@ -52,7 +52,7 @@ pub async fn secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
 
     let members: Vec<_> = members.choose_multiple(&mut rand::thread_rng(), amount).collect();
     if members.is_empty() {
-        return send(conn, number, ErrorResponse::new(req.channel, "no online members found")).await;
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NoOnlineMembers, "no online members found")).await;
     }
 
     let request_id = Uuid::new_v4();
@@ -70,14 +70,14 @@ pub async fn secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientS
             None => continue,
         };
 
-        target_client.read().await.tx.send(ResponseContainer {
+        target_client.read().await.tx.push(ResponseContainer {
             number: 0,
             kind: ResponseKind::SendSecrets(SendSecretsResponse {
                 channel: req.channel,
                 request_id,
                 pk: pk.clone().into(),
             }),
-        }).await?;
+        });
     }
 
     Ok(())
@@ -3,11 +3,11 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, ResponseContainer, State, WsStream};
-use crate::types::protocol::{ResponseKind, SecretsResponse, SendSecretsRequest};
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{ErrorCode, ResponseKind, SecretsResponse, SendSecretsRequest};
 use crate::util::send;
 
-pub async fn send_secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: SendSecretsRequest) -> Result<()> {
+pub async fn send_secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: SendSecretsRequest) -> Result<()> {
     let encrypted = match req.encrypted_shared_secret {
         Some(encrypted) if !encrypted.is_empty() => encrypted,
         _ => return Ok(()),
@@ -19,7 +19,7 @@ pub async fn send_secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<Cl
     };
 
     if client_state.read().await.get_rank_invite(info.channel_id, &state).await?.is_none() {
-        return send(conn, number, ErrorResponse::new(info.channel_id, "not in that channel")).await;
+        return send(out, number, ErrorResponse::new(info.channel_id, ErrorCode::NotInChannel, "not in that channel")).await;
     }
 
     state.write().await.secrets_requests.remove(&req.request_id);
@@ -36,7 +36,7 @@ pub async fn send_secrets(state: Arc<RwLock<State>>, client_state: Arc<RwLock<Cl
             pk: client_state.read().await.pk.clone().into(),
             encrypted_shared_secret: encrypted,
         }),
-    }).await.context("failed to send secrets response")?;
+    }).context("failed to send secrets response")?;
 
     Ok(())
 }
@@ -0,0 +1,85 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::{ClientState, ErrorResponse, Outbound, ResponseContainer, State};
+use crate::types::protocol::{ErrorCode, TypingRequest, TypingResponse};
+use crate::util::send;
+
+/// How often one member's typing in a channel is passed on.
+const TYPING_INTERVAL: Duration = Duration::from_secs(3);
+/// How long a member is shown as typing without another update.
+const TYPING_EXPIRY: Duration = Duration::from_secs(6);
+
+/// Forgets typing updates old enough that they neither hold back the next
+/// nor still show, returning how many were removed.
+pub fn remove_stale_typing(state: &State) -> usize {
+    let mut typing = state.typing.lock();
+    let before = typing.len();
+    let now = Instant::now();
+    typing.retain(|_, (last, _)| now.duration_since(*last) < TYPING_INTERVAL.max(TYPING_EXPIRY));
+    before - typing.len()
+}
+
+pub async fn typing(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: TypingRequest) -> Result<()> {
+    let (lodestone_id, name, world) = match &client_state.read().await.user {
+        Some(u) => (u.lodestone_id, u.name.clone(), u.world),
+        None => return Ok(()),
+    };
+
+    if !client_state.read().await.in_channel(req.channel, &state).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await;
+    }
+
+    let rate_limited = {
+        let state = state.read().await;
+        let mut typing = state.typing.lock();
+        let now = Instant::now();
+        let last = typing.get(&(lodestone_id, req.channel)).copied();
+
+        match last {
+            Some((last, _)) if now.duration_since(last) < TYPING_INTERVAL => true,
+            _ => {
+                // stopping only needs passing on if members are still shown
+                // typing
+                let shown = matches!(last, Some((last, true)) if now.duration_since(last) < TYPING_EXPIRY);
+                if !req.typing && !shown {
+                    return Ok(());
+                }
+
+                typing.insert((lodestone_id, req.channel), (now, req.typing));
+                false
+            }
+        }
+    };
+
+    if rate_limited {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::RateLimited, "typing updates are too frequent")).await;
+    }
+
+    let resp = ResponseContainer {
+        number: 0,
+        kind: TypingResponse {
+            channel: req.channel,
+            name,
+            world: crate::util::id_from_world(world),
+            typing: req.typing,
+            expires_in: TYPING_EXPIRY.as_millis() as u32,
+        }.into(),
+    };
+
+    for member in crate::util::get_raw_members(&state, req.channel).await? {
+        if member.lodestone_id as u64 == lodestone_id {
+            continue;
+        }
+
+        if let Some(client) = state.read().await.clients.get(&(member.lodestone_id as u64)) {
+            client.read().await.tx.push(resp.clone());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,47 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use tokio::sync::RwLock;
+
+use crate::{ClientState, ErrorResponse, Outbound, State};
+use crate::types::protocol::{ErrorCode, MemberChangeKind, MemberChangeResponse, UnbanRequest, UnbanResponse};
+use crate::types::protocol::channel::Rank;
+use crate::util::send;
+
+pub async fn unban(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: UnbanRequest) -> Result<()> {
+    let user = match &client_state.read().await.user {
+        Some(user) => user.clone(),
+        None => return Ok(()),
+    };
+
+    match client_state.read().await.get_rank(req.channel, &state).await? {
+        Some(rank) if rank >= Rank::Moderator => {}
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in channel")).await,
+    }
+
+    let target_id = match state.read().await.get_id(&state, &req.name, req.world).await {
+        Some(id) => id,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::UserNotFound, "user not found")).await,
+    };
+
+    if !crate::types::ban::unban(&state, req.channel, target_id).await? {
+        return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotBanned, "user is not banned")).await;
+    }
+
+    crate::util::send_to_all(&state, req.channel, 0, MemberChangeResponse {
+        channel: req.channel,
+        name: req.name.clone(),
+        world: req.world,
+        kind: MemberChangeKind::Unban {
+            unbanner: user.name,
+            unbanner_world: crate::util::id_from_world(user.world),
+        },
+    }).await?;
+
+    send(out, number, UnbanResponse {
+        channel: req.channel,
+        name: req.name,
+        world: req.world,
+    }).await
+}
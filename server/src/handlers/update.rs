@@ -3,14 +3,15 @@ use std::sync::Arc;
 use anyhow::{Context, Result};
 use tokio::sync::RwLock;
 
-use crate::{ClientState, ErrorResponse, Rank, State, WsStream};
-use crate::types::protocol::{UpdatedResponse, UpdateKind, UpdateRequest, UpdateResponse};
+use crate::{ClientState, ErrorResponse, Outbound, Rank, State};
+use crate::types::protocol::{ErrorCode, UpdatedResponse, UpdateKind, UpdateRequest, UpdateResponse};
 use crate::util::send;
 
-pub async fn update(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, conn: &mut WsStream, number: u32, req: UpdateRequest) -> Result<()> {
+pub async fn update(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: UpdateRequest) -> Result<()> {
     match client_state.read().await.get_rank(req.channel, &state).await? {
         Some(Rank::Admin) => {}
-        _ => return send(conn, number, ErrorResponse::new(req.channel, "not in that channel")).await,
+        Some(_) => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::InsufficientRank, "not enough permissions")).await,
+        None => return send(out, number, ErrorResponse::new(req.channel, ErrorCode::NotInChannel, "not in that channel")).await,
     }
 
     let channel_id_str = req.channel.as_simple().to_string();
@@ -26,6 +27,18 @@ pub async fn update(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
                 .await
                 .context("could not update name")?;
         }
+        UpdateKind::Retention(retention) => {
+            let retention = retention.map(|secs| secs.min(i64::MAX as u64) as i64);
+            sqlx::query!(
+                // language=sqlite
+                "update channels set retention = ? where id = ?",
+                retention,
+                channel_id_str,
+            )
+                .execute(&state.read().await.db)
+                .await
+                .context("could not update retention")?;
+        }
     }
 
     crate::util::send_to_all(&state, req.channel, 0, UpdatedResponse {
@@ -33,7 +46,7 @@ pub async fn update(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientSt
         kind: req.kind,
     }).await?;
 
-    send(conn, number, UpdateResponse {
+    send(out, number, UpdateResponse {
         channel: req.channel,
     }).await
 }
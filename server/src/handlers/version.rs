@@ -1,25 +1,48 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
 use anyhow::Result;
+use tokio::sync::RwLock;
 
 use crate::{
+    ClientState,
     ErrorResponse,
+    Outbound,
     types::protocol::{
+        CAPABILITIES,
+        ErrorCode,
+        MAX_VERSION,
+        MIN_VERSION,
         VersionRequest,
         VersionResponse,
     },
     util::send,
-    WsStream,
 };
 
-const VERSION: u32 = 1;
+pub async fn version(client_state: Arc<RwLock<ClientState>>, out: &Outbound, number: u32, req: VersionRequest) -> Result<bool> {
+    let client_max = req.version;
+    let client_min = req.min_version.unwrap_or(req.version);
 
-pub async fn version(conn: &mut WsStream, number: u32, req: VersionRequest) -> Result<bool> {
-    if req.version != VERSION {
-        send(conn, number, ErrorResponse::new(None, "unsupported version")).await?;
+    // pick the highest version both sides can speak
+    let version = client_max.min(MAX_VERSION);
+    if version < client_min.max(MIN_VERSION) {
+        send(out, number, ErrorResponse::new(None, ErrorCode::UnsupportedVersion, "unsupported version")).await?;
         return Ok(false);
     }
 
-    send(conn, number, VersionResponse {
-        version: VERSION,
+    let capabilities: HashSet<String> = req.capabilities
+        .into_iter()
+        .filter(|cap| CAPABILITIES.contains(&cap.as_str()))
+        .collect();
+
+    let mut c_state = client_state.write().await;
+    c_state.version = version;
+    c_state.capabilities = capabilities.clone();
+    drop(c_state);
+
+    send(out, number, VersionResponse {
+        version,
+        capabilities: capabilities.into_iter().collect(),
     }).await?;
 
     Ok(true)
@@ -40,6 +40,19 @@ pub fn spawn(config: &Config, state: Arc<RwLock<State>>) {
             last_messages = messages;
 
             let clients = state.read().await.clients.len();
+            let decode_failures = state.read().await.decode_failures.load(Ordering::SeqCst);
+            let overflow_disconnects = state.read().await.overflow_disconnects.load(Ordering::SeqCst);
+
+            // summed over every client so the series don't grow with users
+            let mut queued = 0;
+            let mut max_depth = 0;
+            let mut dropped = state.read().await.pushes_dropped.load(Ordering::SeqCst);
+            for client in state.read().await.clients.values() {
+                let tx = &client.read().await.tx;
+                queued += tx.depth();
+                max_depth = max_depth.max(tx.depth());
+                dropped += tx.dropped();
+            }
 
             let num_users = sqlx::query!(
                 // language=sqlite
@@ -78,10 +91,11 @@ pub fn spawn(config: &Config, state: Arc<RwLock<State>>) {
                 .unwrap_or_default();
 
             let mut line_format = format!(
-                "logged_in value={logged_in}u {timestamp}\nmessages_this_instance value={messages_this_instance}u {timestamp}\nmessages_new value={messages_new}u {timestamp}\n",
+                "logged_in value={logged_in}u {timestamp}\nmessages_this_instance value={messages_this_instance}u {timestamp}\nmessages_new value={messages_new}u {timestamp}\ndecode_failures value={decode_failures}u {timestamp}\n",
                 logged_in = clients,
                 messages_this_instance = messages,
                 messages_new = diff,
+                decode_failures = decode_failures,
                 timestamp = timestamp,
             );
 
@@ -117,6 +131,15 @@ pub fn spawn(config: &Config, state: Arc<RwLock<State>>) {
                 ));
             }
 
+            line_format.push_str(&format!(
+                "outbound_queued value={queued}u {timestamp}\noutbound_max_depth value={max_depth}u {timestamp}\noutbound_dropped value={dropped}u {timestamp}\noutbound_overflow_disconnects value={overflow_disconnects}u {timestamp}\n",
+                queued = queued,
+                max_depth = max_depth,
+                dropped = dropped,
+                overflow_disconnects = overflow_disconnects,
+                timestamp = timestamp,
+            ));
+
             debug!("line_format: {}", line_format);
 
             let res = client.post(url.clone())
@@ -0,0 +1,194 @@
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context as TaskContext, Poll};
+
+use anyhow::{Context, Result};
+use log::{error, info};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio::net::{TcpListener, TcpStream, UnixListener, UnixStream};
+use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
+use tokio_rustls::rustls::{Certificate, PrivateKey, ServerConfig};
+use tokio_rustls::server::TlsStream;
+use tokio_rustls::TlsAcceptor;
+use tokio_tungstenite::tungstenite::protocol::WebSocketConfig;
+
+use crate::{State, WsStream};
+use crate::types::config::Listener as ListenerConfig;
+
+/// A connection accepted by any of the configured listeners.
+pub enum Stream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+    Tls(Box<TlsStream<TcpStream>>),
+}
+
+impl AsyncRead for Stream {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut TaskContext<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Unix(s) => Pin::new(s).poll_read(cx, buf),
+            Stream::Tcp(s) => Pin::new(s).poll_read(cx, buf),
+            Stream::Tls(s) => Pin::new(s).poll_read(cx, buf),
+        }
+    }
+}
+
+impl AsyncWrite for Stream {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut TaskContext<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        match self.get_mut() {
+            Stream::Unix(s) => Pin::new(s).poll_write(cx, buf),
+            Stream::Tcp(s) => Pin::new(s).poll_write(cx, buf),
+            Stream::Tls(s) => Pin::new(s).poll_write(cx, buf),
+        }
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Unix(s) => Pin::new(s).poll_flush(cx),
+            Stream::Tcp(s) => Pin::new(s).poll_flush(cx),
+            Stream::Tls(s) => Pin::new(s).poll_flush(cx),
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<io::Result<()>> {
+        match self.get_mut() {
+            Stream::Unix(s) => Pin::new(s).poll_shutdown(cx),
+            Stream::Tcp(s) => Pin::new(s).poll_shutdown(cx),
+            Stream::Tls(s) => Pin::new(s).poll_shutdown(cx),
+        }
+    }
+}
+
+pub enum Listener {
+    Unix(UnixListener),
+    Tcp(TcpListener),
+    Tls(TcpListener, TlsAcceptor),
+}
+
+impl Listener {
+    pub async fn bind(config: &ListenerConfig) -> Result<Self> {
+        let listener = match config {
+            ListenerConfig::Unix { path } => {
+                let listener = UnixListener::bind(path)
+                    .with_context(|| format!("could not bind to {}", path.display()))?;
+                info!("Listening on ws://unix:{}/", path.display());
+                Self::Unix(listener)
+            }
+            ListenerConfig::Tcp { address } => {
+                let listener = TcpListener::bind(address)
+                    .await
+                    .with_context(|| format!("could not bind to {}", address))?;
+                info!("Listening on ws://{}/", address);
+                Self::Tcp(listener)
+            }
+            ListenerConfig::Tls { address, cert, key } => {
+                let acceptor = tls_acceptor(cert, key)?;
+                let listener = TcpListener::bind(address)
+                    .await
+                    .with_context(|| format!("could not bind to {}", address))?;
+                info!("Listening on wss://{}/", address);
+                Self::Tls(listener, acceptor)
+            }
+        };
+
+        Ok(listener)
+    }
+
+    /// Accepts connections forever, running the websocket handshake and
+    /// `client_loop` for each one on its own task.
+    pub fn spawn(self, state: Arc<RwLock<State>>) -> JoinHandle<()> {
+        tokio::task::spawn(async move {
+            loop {
+                let accepted = match &self {
+                    Self::Unix(listener) => listener.accept().await.map(|(sock, _)| Accepted::Ready(Stream::Unix(sock))),
+                    Self::Tcp(listener) => listener.accept().await.map(|(sock, _)| Accepted::Ready(Stream::Tcp(sock))),
+                    Self::Tls(listener, acceptor) => listener.accept().await.map(|(sock, _)| Accepted::Tls(sock, acceptor.clone())),
+                };
+
+                let accepted = match accepted {
+                    Ok(a) => a,
+                    Err(e) => {
+                        error!("server error: {}", e);
+                        continue;
+                    }
+                };
+
+                let state = Arc::clone(&state);
+                tokio::task::spawn(async move {
+                    let max_size = state.read().await.config.server.max_frame_size;
+                    let ws_config = WebSocketConfig {
+                        max_message_size: Some(max_size),
+                        max_frame_size: Some(max_size),
+                        ..Default::default()
+                    };
+
+                    let sock = match accepted {
+                        Accepted::Ready(sock) => sock,
+                        Accepted::Tls(sock, acceptor) => match acceptor.accept(sock).await {
+                            Ok(s) => Stream::Tls(Box::new(s)),
+                            Err(e) => {
+                                error!("tls error: {:?}", e);
+                                return;
+                            }
+                        },
+                    };
+
+                    let conn = match tokio_tungstenite::accept_async_with_config(sock, Some(ws_config)).await {
+                        Ok(c) => c,
+                        Err(e) => {
+                            error!("client error: {:?}", e);
+                            return;
+                        }
+                    };
+
+                    if let Err(e) = crate::client_loop(state, WsStream::new(conn)).await {
+                        error!("client error: {}", e);
+                    }
+                });
+            }
+        })
+    }
+}
+
+enum Accepted {
+    Ready(Stream),
+    Tls(TcpStream, TlsAcceptor),
+}
+
+fn tls_acceptor(cert: &Path, key: &Path) -> Result<TlsAcceptor> {
+    let certs = {
+        let file = File::open(cert)
+            .with_context(|| format!("could not open certificate {}", cert.display()))?;
+        rustls_pemfile::certs(&mut BufReader::new(file))
+            .context("could not read certificate")?
+            .into_iter()
+            .map(Certificate)
+            .collect::<Vec<_>>()
+    };
+
+    let key = {
+        let file = File::open(key)
+            .with_context(|| format!("could not open private key {}", key.display()))?;
+        rustls_pemfile::read_all(&mut BufReader::new(file))
+            .context("could not read private key")?
+            .into_iter()
+            .find_map(|item| match item {
+                rustls_pemfile::Item::PKCS8Key(key)
+                | rustls_pemfile::Item::RSAKey(key)
+                | rustls_pemfile::Item::ECKey(key) => Some(PrivateKey(key)),
+                _ => None,
+            })
+            .context("no private key found")?
+    };
+
+    let config = ServerConfig::builder()
+        .with_safe_defaults()
+        .with_no_client_auth()
+        .with_single_cert(certs, key)
+        .context("invalid certificate or private key")?;
+
+    Ok(TlsAcceptor::from(Arc::new(config)))
+}
@@ -1,6 +1,6 @@
 #![feature(try_blocks)]
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::sync::atomic::{AtomicU64, Ordering};
@@ -15,11 +15,16 @@ use sha3::Digest;
 use sqlx::{ConnectOptions, Executor, Pool, Sqlite};
 use sqlx::migrate::Migrator;
 use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
-use tokio::net::{UnixListener, UnixStream};
 use tokio::sync::mpsc::{Sender, UnboundedSender};
-use tokio::sync::RwLock;
+use tokio::sync::{oneshot, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio::task::JoinSet;
+use tokio::time::{Instant, MissedTickBehavior};
 use tokio_tungstenite::{
-    tungstenite::Message as WsMessage,
+    tungstenite::{
+        Error as WsError,
+        Message as WsMessage,
+        protocol::frame::coding::CloseCode,
+    },
     WebSocketStream,
 };
 use uuid::Uuid;
@@ -37,29 +42,61 @@ use crate::types::{
     user::User,
 };
 use crate::handlers::SecretsRequestInfo;
+use crate::listener::{Listener, Stream};
+use crate::outbound::Outbound;
 use crate::types::config::Config;
-use crate::types::protocol::{AnnounceResponse, AuthenticateRequest, AuthenticateResponse, ErrorResponse, ResponseKind};
+use crate::types::protocol::{AnnounceResponse, AuthenticateRequest, AuthenticateResponse, ErrorCode, ErrorResponse, MIN_VERSION, Requirement, ResponseKind};
 use crate::types::protocol::channel::Rank;
+use crate::types::protocol::codec::{self, Encoding};
 
 pub mod types;
 pub mod handlers;
+pub mod cleanup;
 pub mod util;
 pub mod updater;
 pub mod logging;
 pub mod influx;
+pub mod listener;
+pub mod outbound;
 
 #[global_allocator]
 static ALLOC: mimalloc::MiMalloc = mimalloc::MiMalloc;
 
-pub type WsStream = WebSocketStream<UnixStream>;
+/// A client's websocket, along with the encoding to reply in.
+pub struct WsStream {
+    pub ws: WebSocketStream<Stream>,
+    /// The encoding of the last request received, used for replies and
+    /// pushes alike.
+    pub encoding: Encoding,
+}
+
+impl WsStream {
+    pub fn new(ws: WebSocketStream<Stream>) -> Self {
+        Self {
+            ws,
+            encoding: Encoding::MessagePack,
+        }
+    }
+}
 
 pub struct State {
     pub db: Pool<Sqlite>,
     pub clients: HashMap<u64, Arc<RwLock<ClientState>>>,
     pub ids: HashMap<(String, u16), u64>,
     pub secrets_requests: HashMap<Uuid, SecretsRequestInfo>,
+    /// When each member's last typing update in each channel was passed on,
+    /// and whether it showed them typing. Behind its own lock so typing
+    /// doesn't need the state's write lock.
+    pub typing: parking_lot::Mutex<HashMap<(u64, Uuid), (Instant, bool)>>,
     pub messages_sent: AtomicU64,
+    pub decode_failures: AtomicU64,
+    /// Pushes dropped for clients that have since disconnected. Connected
+    /// clients keep their own count on their [`Outbound`].
+    pub pushes_dropped: AtomicU64,
+    /// Clients disconnected for falling too far behind.
+    pub overflow_disconnects: AtomicU64,
     pub updater_tx: UnboundedSender<i64>,
+    pub config: Arc<Config>,
 }
 
 impl State {
@@ -67,10 +104,10 @@ impl State {
         let msg = msg.into();
 
         for client in self.clients.values() {
-            client.read().await.tx.send(ResponseContainer {
+            client.read().await.tx.push(ResponseContainer {
                 number: 0,
                 kind: ResponseKind::Announce(AnnounceResponse::new(&msg)),
-            }).await.ok();
+            });
         }
     }
 
@@ -105,7 +142,8 @@ async fn main() -> Result<()> {
     let config_path = std::env::args().nth(1).unwrap_or_else(|| "config.toml".to_string());
     let config_toml = std::fs::read_to_string(config_path)
         .context("couldn't read config file")?;
-    let config: Config = toml::from_str(&config_toml)
+    let config: Arc<Config> = toml::from_str(&config_toml)
+        .map(Arc::new)
         .context("couldn't parse config file")?;
 
     // set up database pool
@@ -132,23 +170,30 @@ async fn main() -> Result<()> {
     let (updater_tx, updater_rx) = tokio::sync::mpsc::unbounded_channel();
 
     // set up server
-    let server = UnixListener::bind(&config.server.path)?;
+    let listener_configs = config.server.all_listeners();
+    if listener_configs.is_empty() {
+        anyhow::bail!("no listeners configured");
+    }
+
+    let mut listeners = Vec::with_capacity(listener_configs.len());
+    for listener_config in &listener_configs {
+        listeners.push(Listener::bind(listener_config).await?);
+    }
+
     let state = Arc::new(RwLock::new(State {
         db: pool,
         clients: Default::default(),
         ids: Default::default(),
         secrets_requests: Default::default(),
+        typing: Default::default(),
         messages_sent: AtomicU64::default(),
+        decode_failures: AtomicU64::default(),
+        pushes_dropped: AtomicU64::default(),
+        overflow_disconnects: AtomicU64::default(),
         updater_tx,
+        config: Arc::clone(&config),
     }));
 
-    let listening_on = server.local_addr()
-        .ok()
-        .and_then(|addr| addr.as_pathname().map(ToOwned::to_owned))
-        .and_then(|addr| addr.to_str().map(ToOwned::to_owned))
-        .unwrap_or_else(|| config.server.path.to_string_lossy().to_string());
-    info!("Listening on ws://unix:{listening_on}/");
-
     let (quit_tx, mut quit_rx) = tokio::sync::mpsc::channel(1);
     let (announce_tx, mut announce_rx) = tokio::sync::mpsc::channel(1);
 
@@ -231,39 +276,22 @@ async fn main() -> Result<()> {
 
     updater::spawn(Arc::clone(&state), updater_rx);
 
-    loop {
-        let res: Result<()> = try {
-            tokio::select! {
-                accept = server.accept() => {
-                    let (sock, _addr) = accept?;
-                    let state = Arc::clone(&state);
-                    tokio::task::spawn(async move {
-                        let conn = match tokio_tungstenite::accept_async(sock).await {
-                            Ok(c) => c,
-                            Err(e) => {
-                                error!("client error: {:?}", e);
-                                return;
-                            }
-                        };
+    cleanup::spawn(Arc::clone(&state));
 
-                        if let Err(e) = client_loop(state, conn).await {
-                            error!("client error: {}", e);
-                        }
-                    });
-                }
-                _ = quit_rx.recv() => {
-                    break;
-                }
-                msg = announce_rx.recv() => {
-                    if let Some(msg) = msg {
-                        state.read().await.announce(msg).await;
-                    }
+    for listener in listeners {
+        listener.spawn(Arc::clone(&state));
+    }
+
+    loop {
+        tokio::select! {
+            _ = quit_rx.recv() => {
+                break;
+            }
+            msg = announce_rx.recv() => {
+                if let Some(msg) = msg {
+                    state.read().await.announce(msg).await;
                 }
             }
-        };
-
-        if let Err(e) = res {
-            error!("server error: {}", e);
         }
     }
 
@@ -273,13 +301,24 @@ async fn main() -> Result<()> {
 
 pub struct ClientState {
     user: Option<User>,
-    tx: Sender<ResponseContainer>,
+    tx: Outbound,
     shutdown_tx: Sender<()>,
     pk: Vec<u8>,
     allow_invites: bool,
+    allow_direct_messages: bool,
+    version: u32,
+    capabilities: HashSet<String>,
 }
 
 impl ClientState {
+    /// Whether this connection has negotiated what `requirement` asks for.
+    pub fn supports(&self, requirement: Requirement) -> bool {
+        match requirement {
+            Requirement::Version(version) => self.version >= version,
+            Requirement::Capability(cap) => self.capabilities.contains(cap),
+        }
+    }
+
     pub fn lodestone_id(&self) -> Option<u64> {
         self.user.as_ref().map(|u| u.lodestone_id)
     }
@@ -357,18 +396,100 @@ impl ClientState {
     }
 }
 
+/// How a request must be ordered against the others from its connection.
+enum Lane {
+    /// Runs alongside anything else.
+    Concurrent,
+    /// Runs once every earlier sequential request from the connection has
+    /// finished, so that e.g. a join and the messages after it stay in
+    /// order.
+    Sequential,
+    /// Runs alone, once everything before it has finished. Used for
+    /// requests that change the session itself.
+    Exclusive,
+}
+
+impl Lane {
+    fn of(kind: &RequestKind) -> Self {
+        match kind {
+            RequestKind::Version(_)
+            | RequestKind::Authenticate(_)
+            | RequestKind::DeleteAccount(_) => Self::Exclusive,
+            RequestKind::Message(_)
+            | RequestKind::Create(_)
+            | RequestKind::Disband(_)
+            | RequestKind::Invite(_)
+            | RequestKind::Join(_)
+            | RequestKind::Leave(_)
+            | RequestKind::Kick(_)
+            | RequestKind::Promote(_)
+            | RequestKind::Update(_)
+            | RequestKind::EditMessage(_)
+            | RequestKind::DeleteMessage(_)
+            | RequestKind::React(_)
+            | RequestKind::MarkRead(_)
+            | RequestKind::InviteKey(_)
+            | RequestKind::Ban(_)
+            | RequestKind::Unban(_)
+            | RequestKind::Mute(_) => Self::Sequential,
+            RequestKind::Ping(_)
+            | RequestKind::Register(_)
+            | RequestKind::List(_)
+            | RequestKind::PublicKey(_)
+            | RequestKind::Secrets(_)
+            | RequestKind::SendSecrets(_)
+            | RequestKind::AllowInvites(_)
+            | RequestKind::History(_)
+            | RequestKind::Typing(_)
+            | RequestKind::DirectMessage(_)
+            | RequestKind::AllowDirectMessages(_) => Self::Concurrent,
+        }
+    }
+}
+
 async fn client_loop(state: Arc<RwLock<State>>, mut conn: WsStream) -> Result<()> {
-    let (tx, mut rx) = tokio::sync::mpsc::channel(10);
+    let (out, rx) = outbound::channel(&state.read().await.config.server.outbound);
     let (shutdown_tx, mut shutdown_rx) = tokio::sync::mpsc::channel(1);
 
     let client_state = Arc::new(RwLock::new(ClientState {
         user: None,
-        tx,
+        tx: out.clone(),
         shutdown_tx,
         pk: Default::default(),
         allow_invites: false,
+        allow_direct_messages: false,
+        version: MIN_VERSION,
+        capabilities: Default::default(),
     }));
 
+    let keepalive = state.read().await.config.server.keepalive.clone();
+    let pong_timeout = Duration::from_secs(keepalive.pong_timeout);
+    let idle_timeout = keepalive.idle_timeout.map(Duration::from_secs);
+
+    // the first tick of an interval completes immediately, so start one
+    // period from now instead
+    let ping_period = Duration::from_secs(keepalive.ping_interval.max(1));
+    let mut ping_interval = tokio::time::interval_at(Instant::now() + ping_period, ping_period);
+    ping_interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let mut awaiting_pong = false;
+    let pong_deadline = tokio::time::sleep(pong_timeout);
+    tokio::pin!(pong_deadline);
+
+    let idle_deadline = tokio::time::sleep(idle_timeout.unwrap_or_default());
+    tokio::pin!(idle_deadline);
+
+    // every request in flight holds a permit, and a new request is only
+    // read once one has been acquired
+    let max_in_flight = state.read().await.config.server.max_in_flight.max(1);
+    let in_flight = Arc::new(Semaphore::new(max_in_flight));
+    let mut permit: Option<OwnedSemaphorePermit> = None;
+    // an exclusive request waiting for everything in flight to finish
+    let mut exclusive: Option<RequestContainer> = None;
+    // completes once the latest sequential request has finished
+    let mut sequential_done: Option<oneshot::Receiver<()>> = None;
+    let mut tasks: JoinSet<Result<bool>> = JoinSet::new();
+
     loop {
         let res: Result<()> = try {
             tokio::select! {
@@ -376,93 +497,143 @@ async fn client_loop(state: Arc<RwLock<State>>, mut conn: WsStream) -> Result<()
                     debug!("break due to new login");
                     break;
                 }
+                _ = ping_interval.tick(), if keepalive.ping_interval > 0 && !awaiting_pong => {
+                    conn.ws.send(WsMessage::Ping(Vec::new())).await?;
+                    awaiting_pong = true;
+                    pong_deadline.as_mut().reset(Instant::now() + pong_timeout);
+                }
+                () = &mut pong_deadline, if awaiting_pong => {
+                    debug!("break due to pong timeout");
+                    util::close(&mut conn, CloseCode::Away, "pong timeout").await;
+                    break;
+                }
+                () = &mut idle_deadline, if idle_timeout.is_some() => {
+                    debug!("break due to idle timeout");
+                    util::close(&mut conn, CloseCode::Away, "idle timeout").await;
+                    break;
+                }
                 msg = rx.recv() => {
-                    if let Some(msg) = msg {
-                        let encoded = rmp_serde::to_vec(&msg)?;
-                        conn.send(WsMessage::Binary(encoded)).await?;
+                    // don't push anything the client didn't negotiate
+                    if client_state.read().await.supports(msg.container.kind.requirement()) {
+                        // a client that stops reading leaves this write
+                        // pending, so give up on it once the queue has
+                        // been full for too long
+                        tokio::select! {
+                            res = util::send_container(&mut conn, &msg.container) => res?,
+                            () = rx.overflowed() => {
+                                debug!("break due to full outbound queue");
+                                state.read().await.overflow_disconnects.fetch_add(1, Ordering::SeqCst);
+                                util::close(&mut conn, CloseCode::Again, "too far behind").await;
+                                break;
+                            }
+                        }
+                        msg.written();
                     }
                 }
-                msg = conn.next() => {
+                Some(res) = tasks.join_next(), if !tasks.is_empty() => {
+                    if !res.context("request handler panicked")?? {
+                        // deliver whatever the handler sent before closing
+                        while let Some(msg) = rx.try_recv() {
+                            util::send_container(&mut conn, &msg.container).await?;
+                            msg.written();
+                        }
+
+                        debug!("break due to handler");
+                        break;
+                    }
+                }
+                acquired = Arc::clone(&in_flight).acquire_owned(), if permit.is_none() && exclusive.is_none() => {
+                    permit = Some(acquired?);
+                }
+                acquired = Arc::clone(&in_flight).acquire_many_owned(max_in_flight as u32), if exclusive.is_some() => {
+                    let permits = acquired?;
+                    if let Some(msg) = exclusive.take() {
+                        let (state, client_state, out) = (Arc::clone(&state), Arc::clone(&client_state), out.clone());
+                        tasks.spawn(async move {
+                            let _permits = permits;
+                            dispatch(state, client_state, out, msg).await
+                        });
+                    }
+                }
+                msg = conn.ws.next(), if permit.is_some() && exclusive.is_none() => {
                     // match &msg {
                     //     Some(Ok(WsMessage::Pong(_))) => {},
                     //     _ => debug!("{:?}", msg),
                     // }
 
                     match msg {
-                        Some(Ok(WsMessage::Binary(msg))) => {
-                            let msg: RequestContainer = rmp_serde::from_slice(&msg)?;
-                            debug!("{:#?}", msg);
-
-                            let logged_in = client_state.read().await.user.is_some();
+                        Some(Ok(WsMessage::Pong(_))) => {
+                            awaiting_pong = false;
+                        }
+                        Some(Ok(frame @ (WsMessage::Binary(_) | WsMessage::Text(_)))) => {
+                            if let Some(idle_timeout) = idle_timeout {
+                                idle_deadline.as_mut().reset(Instant::now() + idle_timeout);
+                            }
 
-                            match msg.kind {
-                                RequestKind::Ping(_) => {
-                                    crate::handlers::ping(&mut conn, msg.number).await?;
-                                }
-                                RequestKind::Version(req) => {
-                                    if !crate::handlers::version(&mut conn, msg.number, req).await? {
-                                        break;
-                                    }
-                                }
-                                RequestKind::Register(req) => {
-                                    crate::handlers::register(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Authenticate(req) => {
-                                    crate::handlers::authenticate(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Create(req) if logged_in => {
-                                    crate::handlers::create(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
+                            let decoded = match frame {
+                                WsMessage::Text(text) => {
+                                    conn.encoding = Encoding::Json;
+                                    codec::decode_request_json(&text)
                                 }
-                                RequestKind::PublicKey(req) if logged_in => {
-                                    crate::handlers::public_key(Arc::clone(&state), &mut conn, msg.number, req).await?;
+                                frame => {
+                                    conn.encoding = Encoding::MessagePack;
+                                    codec::decode_request(&frame.into_data())
                                 }
-                                RequestKind::Invite(req) if logged_in => {
-                                    crate::handlers::invite(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
+                            };
+
+                            let msg = match decoded {
+                                Ok(msg) => msg,
+                                Err(failure) => {
+                                    state.read().await.decode_failures.fetch_add(1, Ordering::SeqCst);
+                                    warn!("could not decode request: {}", failure.error);
+                                    util::send_container(&mut conn, &ResponseContainer {
+                                        number: failure.number.unwrap_or(0),
+                                        kind: ErrorResponse::new(None, ErrorCode::InvalidRequest, "unknown or malformed request").into(),
+                                    }).await?;
+                                    continue;
                                 }
-                                RequestKind::Join(req) if logged_in => {
-                                    crate::handlers::join(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Message(req) if logged_in => {
-                                    crate::handlers::message(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::List(req) if logged_in => {
-                                    crate::handlers::list(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Leave(req) if logged_in => {
-                                    crate::handlers::leave(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Promote(req) if logged_in => {
-                                    crate::handlers::promote(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Kick(req) if logged_in => {
-                                    crate::handlers::kick(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Disband(req) if logged_in => {
-                                    crate::handlers::disband(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Update(req) if logged_in => {
-                                    crate::handlers::update(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::Secrets(req) if logged_in => {
-                                    crate::handlers::secrets(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::SendSecrets(req) if logged_in => {
-                                    crate::handlers::send_secrets(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::AllowInvites(req) if logged_in => {
-                                    crate::handlers::allow_invites(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
-                                }
-                                RequestKind::DeleteAccount(req) if logged_in => {
-                                    crate::handlers::delete_account(Arc::clone(&state), Arc::clone(&client_state), &mut conn, msg.number, req).await?;
+                            };
+                            debug!("{:#?}", msg);
+
+                            let (state, client_state, out) = (Arc::clone(&state), Arc::clone(&client_state), out.clone());
+                            match Lane::of(&msg.kind) {
+                                Lane::Concurrent => {
+                                    let permit = permit.take();
+                                    tasks.spawn(async move {
+                                        let _permit = permit;
+                                        dispatch(state, client_state, out, msg).await
+                                    });
                                 }
-                                _ if !logged_in => {
-                                    util::send(&mut conn, msg.number, ErrorResponse::new(None, "not logged in")).await?;
+                                Lane::Sequential => {
+                                    let permit = permit.take();
+                                    let previous = sequential_done.take();
+                                    let (done_tx, done_rx) = oneshot::channel::<()>();
+                                    sequential_done = Some(done_rx);
+
+                                    tasks.spawn(async move {
+                                        let _permit = permit;
+                                        let _done = done_tx;
+                                        if let Some(previous) = previous {
+                                            // resolves when the previous task drops its sender
+                                            previous.await.ok();
+                                        }
+
+                                        dispatch(state, client_state, out, msg).await
+                                    });
                                 }
-                                _ => {
-                                    util::send(&mut conn, msg.number, ErrorResponse::new(None, "not yet implemented")).await?;
+                                Lane::Exclusive => {
+                                    // stop reading until everything in flight is done
+                                    permit = None;
+                                    exclusive = Some(msg);
                                 }
                             }
                         }
+                        Some(Err(WsError::Capacity(e))) => {
+                            debug!("break due to oversized message: {}", e);
+                            state.read().await.decode_failures.fetch_add(1, Ordering::SeqCst);
+                            util::close(&mut conn, CloseCode::Size, "message too large").await;
+                            break;
+                        }
                         None | Some(Ok(WsMessage::Close(_))) | Some(Err(_)) => {
                             debug!("break");
                             break;
@@ -481,12 +652,135 @@ async fn client_loop(state: Arc<RwLock<State>>, mut conn: WsStream) -> Result<()
 
     debug!("ending client thread");
 
+    // stop anything still in flight
+    tasks.shutdown().await;
+
     if let Some(user) = &client_state.read().await.user {
         state.write().await.clients.remove(&user.lodestone_id);
         state.write().await.ids.remove(&(user.name.clone(), util::id_from_world(user.world)));
     }
 
+    // counted after leaving `clients` so reports never see these twice
+    state.read().await.pushes_dropped.fetch_add(out.dropped(), Ordering::SeqCst);
+
     debug!("client thread ended");
 
     Ok(())
 }
+
+/// Handles one request, replying through `out`. Returns false if the
+/// connection should be closed.
+async fn dispatch(state: Arc<RwLock<State>>, client_state: Arc<RwLock<ClientState>>, out: Outbound, msg: RequestContainer) -> Result<bool> {
+    let out = &out;
+
+    if !client_state.read().await.supports(msg.kind.requirement()) {
+        util::send(out, msg.number, ErrorResponse::new(None, ErrorCode::UnsupportedRequest, "request not supported by negotiated version")).await?;
+        return Ok(true);
+    }
+
+    let logged_in = client_state.read().await.user.is_some();
+
+    match msg.kind {
+        RequestKind::Ping(_) => {
+            crate::handlers::ping(out, msg.number).await?;
+        }
+        RequestKind::Version(req) => {
+            return crate::handlers::version(client_state, out, msg.number, req).await;
+        }
+        RequestKind::Register(req) => {
+            crate::handlers::register(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Authenticate(req) => {
+            crate::handlers::authenticate(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Create(req) if logged_in => {
+            crate::handlers::create(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::PublicKey(req) if logged_in => {
+            crate::handlers::public_key(state, out, msg.number, req).await?;
+        }
+        RequestKind::Invite(req) if logged_in => {
+            crate::handlers::invite(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Join(req) if logged_in => {
+            crate::handlers::join(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Message(req) if logged_in => {
+            crate::handlers::message(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::List(req) if logged_in => {
+            crate::handlers::list(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Leave(req) if logged_in => {
+            crate::handlers::leave(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Promote(req) if logged_in => {
+            crate::handlers::promote(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Kick(req) if logged_in => {
+            crate::handlers::kick(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Disband(req) if logged_in => {
+            crate::handlers::disband(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Update(req) if logged_in => {
+            crate::handlers::update(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Secrets(req) if logged_in => {
+            crate::handlers::secrets(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::SendSecrets(req) if logged_in => {
+            crate::handlers::send_secrets(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::AllowInvites(req) if logged_in => {
+            crate::handlers::allow_invites(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::DeleteAccount(req) if logged_in => {
+            crate::handlers::delete_account(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::History(req) if logged_in => {
+            crate::handlers::history(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::EditMessage(req) if logged_in => {
+            crate::handlers::edit_message(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::DeleteMessage(req) if logged_in => {
+            crate::handlers::delete_message(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::React(req) if logged_in => {
+            crate::handlers::react(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Typing(req) if logged_in => {
+            crate::handlers::typing(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::MarkRead(req) if logged_in => {
+            crate::handlers::mark_read(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::DirectMessage(req) if logged_in => {
+            crate::handlers::direct_message(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::AllowDirectMessages(req) if logged_in => {
+            crate::handlers::allow_direct_messages(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::InviteKey(req) if logged_in => {
+            crate::handlers::invite_key(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Ban(req) if logged_in => {
+            crate::handlers::ban(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Unban(req) if logged_in => {
+            crate::handlers::unban(state, client_state, out, msg.number, req).await?;
+        }
+        RequestKind::Mute(req) if logged_in => {
+            crate::handlers::mute(state, client_state, out, msg.number, req).await?;
+        }
+        _ if !logged_in => {
+            util::send(out, msg.number, ErrorResponse::new(None, ErrorCode::NotLoggedIn, "not logged in")).await?;
+        }
+        _ => {
+            util::send(out, msg.number, ErrorResponse::new(None, ErrorCode::NotImplemented, "not yet implemented")).await?;
+        }
+    }
+
+    Ok(true)
+}
@@ -0,0 +1,244 @@
+//! The queue of responses waiting to be written to one client.
+
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use parking_lot::Mutex;
+use tokio::sync::{Notify, oneshot};
+use tokio::time::Instant;
+
+use crate::types::config::{OutboundQueue, OverflowPolicy};
+use crate::types::protocol::ResponseContainer;
+
+/// Sends to one client. Replies and pushes share this queue, so a reply
+/// can't overtake a push queued before it.
+///
+/// Nothing here waits on the client. Replies are always queued, since a
+/// client can only have so many requests in flight, while pushes to a
+/// client that isn't keeping up are handled according to the configured
+/// [`OverflowPolicy`].
+#[derive(Clone)]
+pub struct Outbound {
+    inner: Arc<Inner>,
+}
+
+/// The client loop's end of an [`Outbound`].
+pub struct OutboundReceiver {
+    inner: Arc<Inner>,
+}
+
+struct Inner {
+    queue: Mutex<Queue>,
+    /// woken when something is queued
+    ready: Notify,
+    /// woken when the queue becomes full
+    full: Notify,
+    capacity: usize,
+    policy: OverflowPolicy,
+    disconnect_after: Duration,
+    dropped: AtomicU64,
+}
+
+#[derive(Default)]
+struct Queue {
+    items: VecDeque<Entry>,
+    /// when the queue last reached capacity, if it's still there
+    full_since: Option<Instant>,
+    closed: bool,
+}
+
+struct Entry {
+    container: ResponseContainer,
+    /// whether the overflow policy may drop this
+    droppable: bool,
+    /// told once this has been written
+    written: Option<oneshot::Sender<()>>,
+}
+
+/// A response taken off the queue to be written.
+pub struct Outgoing {
+    pub container: ResponseContainer,
+    written: Option<oneshot::Sender<()>>,
+}
+
+impl Outgoing {
+    /// Call once the response has been written to the socket.
+    pub fn written(self) {
+        if let Some(tx) = self.written {
+            tx.send(()).ok();
+        }
+    }
+}
+
+/// The client loop is gone.
+#[derive(Debug)]
+pub struct Disconnected;
+
+impl std::fmt::Display for Disconnected {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("client disconnected")
+    }
+}
+
+impl std::error::Error for Disconnected {}
+
+pub fn channel(config: &OutboundQueue) -> (Outbound, OutboundReceiver) {
+    let inner = Arc::new(Inner {
+        queue: Default::default(),
+        ready: Notify::new(),
+        full: Notify::new(),
+        capacity: config.capacity.max(1),
+        policy: config.policy,
+        disconnect_after: Duration::from_secs(config.disconnect_after),
+        dropped: AtomicU64::default(),
+    });
+
+    (
+        Outbound {
+            inner: Arc::clone(&inner),
+        },
+        OutboundReceiver {
+            inner,
+        },
+    )
+}
+
+impl Outbound {
+    /// Queues a response that must not be dropped, such as a reply.
+    pub fn send(&self, container: ResponseContainer) -> Result<(), Disconnected> {
+        let mut queue = self.inner.queue.lock();
+        if queue.closed {
+            return Err(Disconnected);
+        }
+
+        self.inner.enqueue(&mut queue, container, false, None);
+        Ok(())
+    }
+
+    /// Like [`send`](Self::send), but the returned receiver completes once
+    /// the response has been written to the socket. It fails instead if
+    /// the client disconnects first.
+    pub fn send_tracked(&self, container: ResponseContainer) -> Result<oneshot::Receiver<()>, Disconnected> {
+        let mut queue = self.inner.queue.lock();
+        if queue.closed {
+            return Err(Disconnected);
+        }
+
+        let (tx, rx) = oneshot::channel();
+        self.inner.enqueue(&mut queue, container, false, Some(tx));
+        Ok(rx)
+    }
+
+    /// Queues a push without waiting, applying the overflow policy if the
+    /// client has fallen behind. Pushes to a disconnected client are
+    /// ignored.
+    ///
+    /// Under [`OverflowPolicy::DropOldest`], if the queue is full of
+    /// responses that can't be dropped, this push is dropped instead so the
+    /// queue can't grow without bound.
+    ///
+    /// Returns whether the push was queued.
+    pub fn push(&self, container: ResponseContainer) -> bool {
+        let mut queue = self.inner.queue.lock();
+        if queue.closed {
+            return false;
+        }
+
+        if self.inner.policy == OverflowPolicy::DropOldest && queue.items.len() >= self.inner.capacity {
+            self.inner.dropped.fetch_add(1, Ordering::Relaxed);
+            match queue.items.iter().position(|entry| entry.droppable) {
+                Some(idx) => {
+                    queue.items.remove(idx);
+                }
+                None => return false,
+            }
+        }
+
+        self.inner.enqueue(&mut queue, container, true, None);
+        true
+    }
+
+    /// How many responses are waiting to be written.
+    pub fn depth(&self) -> usize {
+        self.inner.queue.lock().items.len()
+    }
+
+    /// How many pushes have been dropped because the client fell behind.
+    pub fn dropped(&self) -> u64 {
+        self.inner.dropped.load(Ordering::Relaxed)
+    }
+}
+
+impl Inner {
+    fn enqueue(&self, queue: &mut Queue, container: ResponseContainer, droppable: bool, written: Option<oneshot::Sender<()>>) {
+        queue.items.push_back(Entry {
+            container,
+            droppable,
+            written,
+        });
+
+        if queue.items.len() >= self.capacity && queue.full_since.is_none() {
+            queue.full_since = Some(Instant::now());
+            self.full.notify_one();
+        }
+
+        self.ready.notify_one();
+    }
+}
+
+impl OutboundReceiver {
+    /// Waits for the next response to write. Cancel safe.
+    pub async fn recv(&self) -> Outgoing {
+        loop {
+            if let Some(outgoing) = self.try_recv() {
+                return outgoing;
+            }
+
+            self.inner.ready.notified().await;
+        }
+    }
+
+    pub fn try_recv(&self) -> Option<Outgoing> {
+        let mut queue = self.inner.queue.lock();
+        let entry = queue.items.pop_front();
+        if queue.items.len() < self.inner.capacity {
+            queue.full_since = None;
+        }
+
+        entry.map(|entry| Outgoing {
+            container: entry.container,
+            written: entry.written,
+        })
+    }
+
+    /// Completes once the queue has been full for longer than the overflow
+    /// policy allows. Never completes under [`OverflowPolicy::DropOldest`].
+    pub async fn overflowed(&self) {
+        if self.inner.policy != OverflowPolicy::Disconnect {
+            return std::future::pending().await;
+        }
+
+        loop {
+            let full_since = self.inner.queue.lock().full_since;
+            match full_since {
+                Some(since) => {
+                    tokio::time::sleep_until(since + self.inner.disconnect_after).await;
+                    if self.inner.queue.lock().full_since == Some(since) {
+                        return;
+                    }
+                }
+                None => self.inner.full.notified().await,
+            }
+        }
+    }
+}
+
+impl Drop for OutboundReceiver {
+    fn drop(&mut self) {
+        let mut queue = self.inner.queue.lock();
+        queue.closed = true;
+        queue.items.clear();
+    }
+}
@@ -0,0 +1,153 @@
+//! Database access for per-channel bans.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, Utc};
+use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::State;
+use crate::types::protocol::ChannelBan;
+
+/// Whether a ban on the character is in force. Expired bans count as lifted
+/// even before they're cleaned up.
+pub async fn is_banned(state: &RwLock<State>, channel: Uuid, lodestone_id: u64) -> Result<bool> {
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
+    sqlx::query!(
+        // language=sqlite
+        "select count(*) as count from channel_bans where channel_id = ? and lodestone_id = ? and (expires_at is null or expires_at > ?)",
+        channel_id,
+        lodestone_id,
+        now,
+    )
+        .fetch_one(&state.read().await.db)
+        .await
+        .context("could not check for ban")
+        .map(|x| x.count > 0)
+}
+
+/// Bans the character, taking them out of the channel and cancelling any
+/// invite. Banning someone already banned replaces the ban.
+pub async fn ban(state: &RwLock<State>, channel: Uuid, lodestone_id: u64, banner: u64, reason: Option<&[u8]>, expires_at: Option<NaiveDateTime>) -> Result<()> {
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    let banner = banner as i64;
+
+    let mut tx = state.read().await.db.begin().await.context("could not start transaction")?;
+
+    sqlx::query!(
+        // language=sqlite
+        "delete from user_channels where channel_id = ? and lodestone_id = ?",
+        channel_id,
+        lodestone_id,
+    )
+        .execute(&mut *tx)
+        .await
+        .context("could not remove banned user")?;
+
+    sqlx::query!(
+        // language=sqlite
+        "delete from channel_invites where channel_id = ? and invited = ?",
+        channel_id,
+        lodestone_id,
+    )
+        .execute(&mut *tx)
+        .await
+        .context("could not delete invite")?;
+
+    sqlx::query!(
+        // language=sqlite
+        "insert or replace into channel_bans (channel_id, lodestone_id, banner, reason, expires_at) values (?, ?, ?, ?, ?)",
+        channel_id,
+        lodestone_id,
+        banner,
+        reason,
+        expires_at,
+    )
+        .execute(&mut *tx)
+        .await
+        .context("could not add ban")?;
+
+    tx.commit().await.context("could not commit ban")?;
+
+    Ok(())
+}
+
+/// Lifts a ban, returning whether there was one in force.
+pub async fn unban(state: &RwLock<State>, channel: Uuid, lodestone_id: u64) -> Result<bool> {
+    let in_force = is_banned(state, channel, lodestone_id).await?;
+
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    sqlx::query!(
+        // language=sqlite
+        "delete from channel_bans where channel_id = ? and lodestone_id = ?",
+        channel_id,
+        lodestone_id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not remove ban")?;
+
+    Ok(in_force)
+}
+
+pub async fn get_all(state: &RwLock<State>, channel: Uuid) -> Result<Vec<ChannelBan>> {
+    let channel_id = channel.as_simple().to_string();
+    let now = Utc::now().naive_utc();
+    let bans = sqlx::query!(
+        // language=sqlite
+        r#"
+        select banned.name as "name!",
+               banned.world as "world!",
+               banner.name as "banner!",
+               banner.world as "banner_world!",
+               channel_bans.reason,
+               channel_bans.created_at,
+               channel_bans.expires_at
+        from channel_bans
+            inner join users banned on banned.lodestone_id = channel_bans.lodestone_id
+            inner join users banner on banner.lodestone_id = channel_bans.banner
+        where channel_bans.channel_id = ?
+          and (channel_bans.expires_at is null or channel_bans.expires_at > ?)
+        order by channel_bans.created_at
+        "#,
+        channel_id,
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get bans")?;
+
+    let world = |world: &str| World::from_str(world).map(crate::util::id_from_world).unwrap_or(0);
+    Ok(bans
+        .into_iter()
+        .map(|ban| ChannelBan {
+            world: world(&ban.world),
+            name: ban.name,
+            banner_world: world(&ban.banner_world),
+            banner: ban.banner,
+            reason: ban.reason.map(Into::into),
+            created_at: crate::types::message::millis(ban.created_at),
+            expires_at: ban.expires_at.map(crate::types::message::millis),
+        })
+        .collect())
+}
+
+/// Deletes bans that have expired, returning how many were removed.
+pub async fn remove_expired(state: &RwLock<State>) -> Result<u64> {
+    let now = Utc::now().naive_utc();
+    sqlx::query!(
+        // language=sqlite
+        "delete from channel_bans where expires_at <= ?",
+        now,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not remove expired bans")
+        .map(|result| result.rows_affected())
+}
@@ -0,0 +1,289 @@
+//! Database loaders for the channel types in the protocol crate.
+
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::Utc;
+use futures_util::StreamExt;
+use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::State;
+use crate::types::protocol::{InvitedResponse, PendingInvite};
+use crate::types::protocol::channel::{Channel, ChannelMember, Rank, SimpleChannel};
+
+pub async fn get(state: &RwLock<State>, id: Uuid) -> Result<Option<Channel>> {
+    let id_str = id.as_simple().to_string();
+    let raw_channel = sqlx::query!(
+        // language=sqlite
+        "select * from channels where id = ?",
+        id_str,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not get channel info")?;
+
+    let raw_channel = match raw_channel {
+        Some(channel) => channel,
+        None => return Ok(None),
+    };
+
+    let mutes = &crate::types::mute::get_all(state, id).await?;
+    let members: Vec<_> = futures_util::stream::iter(crate::util::get_raw_members(state, id).await?
+        .into_iter()
+        .chain(crate::util::get_raw_invited_members(state, id).await?.into_iter()))
+        .then(|member| async move {
+            ChannelMember {
+                name: member.name,
+                world: World::from_str(&member.world).map(crate::util::id_from_world).unwrap_or(0),
+                rank: Rank::from_u8(member.rank as u8),
+                online: state.read().await.clients.contains_key(&(member.lodestone_id as u64)),
+                muted_until: mutes.get(&(member.lodestone_id as u64)).copied(),
+            }
+        })
+        .collect()
+        .await;
+
+    let id = Uuid::from_str(&raw_channel.id)
+        .context("invalid channel id")?;
+
+    Ok(Some(Channel {
+        id,
+        name: raw_channel.name,
+        members,
+        last_read: 0,
+        unread: 0,
+    }))
+}
+
+pub async fn get_all_for_user(state: &RwLock<State>, lodestone_id: u64) -> Result<Vec<SimpleChannel>> {
+    let lodestone_id_i = lodestone_id as i64;
+
+    let all_channels = sqlx::query!(
+        // language=sqlite
+        "select channels.*, user_channels.rank, user_channels.last_read from user_channels inner join channels on user_channels.channel_id = channels.id where user_channels.lodestone_id = ?",
+        lodestone_id_i,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get channels")?;
+
+    let mut channels = Vec::with_capacity(all_channels.len());
+    for channel in all_channels {
+        let id = match Uuid::from_str(&channel.id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        channels.push(SimpleChannel {
+            id,
+            name: channel.name,
+            rank: Rank::from_u8(channel.rank as u8),
+            last_read: channel.last_read.max(0) as u64,
+            unread: (channel.last_sequence - channel.last_read).max(0) as u64,
+        });
+    }
+
+    Ok(channels)
+}
+
+/// Moves a member's read marker forward to `sequence`, but no further than
+/// the last message sent, returning where it ends up.
+pub async fn mark_read(state: &RwLock<State>, channel: Uuid, lodestone_id: u64, sequence: u64) -> Result<u64> {
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    let sequence = sequence.min(i64::MAX as u64) as i64;
+    let marker = sqlx::query!(
+        // language=sqlite
+        r#"
+        update user_channels
+        set last_read = max(last_read, min(?, (select last_sequence from channels where id = user_channels.channel_id)))
+        where channel_id = ? and lodestone_id = ?
+        returning last_read as "last_read!"
+        "#,
+        sequence,
+        channel_id,
+        lodestone_id,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not update read marker")?;
+
+    Ok(marker.map(|marker| marker.last_read.max(0) as u64).unwrap_or_default())
+}
+
+/// Invites encrypted to the user's invite key, as they'd have been sent had
+/// the user been online.
+pub async fn get_pending_invites(state: &RwLock<State>, lodestone_id: u64) -> Result<Vec<InvitedResponse>> {
+    let lodestone_id = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
+    let invites = sqlx::query!(
+        // language=sqlite
+        r#"
+        select channel_invites.channel_id,
+               channel_invites.encrypted_secret as "encrypted_secret!",
+               channel_invites.inviter_pk as "inviter_pk!",
+               users.name,
+               users.world
+        from channel_invites
+            inner join users on users.lodestone_id = channel_invites.inviter
+        where channel_invites.invited = ?
+          and channel_invites.encrypted_secret is not null
+          and channel_invites.inviter_pk is not null
+          and (channel_invites.expires_at is null or channel_invites.expires_at > ?)
+        "#,
+        lodestone_id,
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get pending invites")?;
+
+    let mut pending = Vec::with_capacity(invites.len());
+    for invite in invites {
+        let id = match Uuid::from_str(&invite.channel_id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        let channel = match get(state, id).await? {
+            Some(channel) => channel,
+            None => continue,
+        };
+
+        pending.push(InvitedResponse {
+            channel,
+            name: invite.name,
+            world: World::from_str(&invite.world).map(crate::util::id_from_world).unwrap_or(0),
+            pk: invite.inviter_pk.into(),
+            encrypted_secret: invite.encrypted_secret.into(),
+            invite_key: true,
+        });
+    }
+
+    Ok(pending)
+}
+
+/// A channel's invites that haven't been accepted or declined yet.
+pub async fn get_channel_invites(state: &RwLock<State>, channel: Uuid) -> Result<Vec<PendingInvite>> {
+    let channel_id = channel.as_simple().to_string();
+    let now = Utc::now().naive_utc();
+    let invites = sqlx::query!(
+        // language=sqlite
+        r#"
+        select invited.name as "name!",
+               invited.world as "world!",
+               inviter.name as "inviter!",
+               inviter.world as "inviter_world!",
+               channel_invites.created_at,
+               channel_invites.expires_at
+        from channel_invites
+            inner join users invited on invited.lodestone_id = channel_invites.invited
+            inner join users inviter on inviter.lodestone_id = channel_invites.inviter
+        where channel_invites.channel_id = ?
+          and (channel_invites.expires_at is null or channel_invites.expires_at > ?)
+        order by channel_invites.created_at
+        "#,
+        channel_id,
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get channel invites")?;
+
+    let world = |world: &str| World::from_str(world).map(crate::util::id_from_world).unwrap_or(0);
+    Ok(invites
+        .into_iter()
+        .map(|invite| PendingInvite {
+            world: world(&invite.world),
+            name: invite.name,
+            inviter_world: world(&invite.inviter_world),
+            inviter: invite.inviter,
+            created_at: crate::types::message::millis(invite.created_at),
+            expires_at: invite.expires_at.map(crate::types::message::millis),
+        })
+        .collect())
+}
+
+/// An invite removed because it expired.
+pub struct ExpiredInvite {
+    pub channel: Uuid,
+    pub lodestone_id: u64,
+    pub name: String,
+    pub world: u16,
+}
+
+/// Deletes invites that have passed their expiry.
+pub async fn remove_expired_invites(state: &RwLock<State>) -> Result<Vec<ExpiredInvite>> {
+    let now = Utc::now().naive_utc();
+    let removed = sqlx::query!(
+        // language=sqlite
+        "delete from channel_invites where expires_at <= ? returning channel_id, invited",
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not remove expired invites")?;
+
+    let mut expired = Vec::with_capacity(removed.len());
+    for invite in removed {
+        let channel = match Uuid::from_str(&invite.channel_id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        let user = sqlx::query!(
+            // language=sqlite
+            "select name, world from users where lodestone_id = ?",
+            invite.invited,
+        )
+            .fetch_optional(&state.read().await.db)
+            .await
+            .context("could not get invited user")?;
+
+        if let Some(user) = user {
+            expired.push(ExpiredInvite {
+                channel,
+                lodestone_id: invite.invited as u64,
+                name: user.name,
+                world: World::from_str(&user.world).map(crate::util::id_from_world).unwrap_or(0),
+            });
+        }
+    }
+
+    Ok(expired)
+}
+
+pub async fn get_invites_for_user(state: &RwLock<State>, lodestone_id: u64) -> Result<Vec<SimpleChannel>> {
+    let lodestone_id_i = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
+
+    let all_channels = sqlx::query!(
+        // language=sqlite
+        "select channels.* from channel_invites inner join channels on channel_invites.channel_id = channels.id where channel_invites.invited = ? and (channel_invites.expires_at is null or channel_invites.expires_at > ?)",
+        lodestone_id_i,
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get channels")?;
+
+    let mut channels = Vec::with_capacity(all_channels.len());
+    for channel in all_channels {
+        let id = match Uuid::from_str(&channel.id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        channels.push(SimpleChannel {
+            id,
+            name: channel.name,
+            rank: Rank::Member,
+            last_read: 0,
+            unread: 0,
+        });
+    }
+
+    Ok(channels)
+}
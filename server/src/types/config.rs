@@ -1,3 +1,4 @@
+use std::net::SocketAddr;
 use std::path::PathBuf;
 
 use serde::{Deserialize, Serialize};
@@ -8,12 +9,122 @@ pub struct Config {
     pub server: Server,
     pub database: Database,
     #[serde(default)]
+    pub messages: Messages,
+    #[serde(default)]
     pub influx: Option<Influx>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Server {
-    pub path: PathBuf,
+    /// Path of a single unix socket to listen on. Kept so older configs
+    /// keep working; prefer `listeners`.
+    #[serde(default)]
+    pub path: Option<PathBuf>,
+    #[serde(default)]
+    pub listeners: Vec<Listener>,
+    #[serde(default)]
+    pub keepalive: Keepalive,
+    /// Largest websocket message, in bytes, a client may send.
+    #[serde(default = "default_max_frame_size")]
+    pub max_frame_size: usize,
+    /// How many requests from one connection may be handled at once.
+    #[serde(default = "default_max_in_flight")]
+    pub max_in_flight: usize,
+    #[serde(default)]
+    pub outbound: OutboundQueue,
+}
+
+fn default_max_frame_size() -> usize {
+    256 * 1024
+}
+
+fn default_max_in_flight() -> usize {
+    8
+}
+
+impl Server {
+    /// All listeners to bind, including the legacy `path` if set.
+    pub fn all_listeners(&self) -> Vec<Listener> {
+        self.path
+            .iter()
+            .map(|path| Listener::Unix { path: path.clone() })
+            .chain(self.listeners.iter().cloned())
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Listener {
+    Unix {
+        path: PathBuf,
+    },
+    Tcp {
+        address: SocketAddr,
+    },
+    Tls {
+        address: SocketAddr,
+        cert: PathBuf,
+        key: PathBuf,
+    },
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Keepalive {
+    /// Seconds between websocket pings sent by the server. Zero disables
+    /// pings (and therefore the pong deadline).
+    pub ping_interval: u64,
+    /// Seconds to wait for a pong before the connection is considered dead.
+    pub pong_timeout: u64,
+    /// Seconds without any request from the client before it is
+    /// disconnected. Unset means idle clients are never disconnected.
+    pub idle_timeout: Option<u64>,
+}
+
+impl Default for Keepalive {
+    fn default() -> Self {
+        Self {
+            ping_interval: 30,
+            pong_timeout: 30,
+            idle_timeout: None,
+        }
+    }
+}
+
+/// The queue of responses waiting to be written to each client.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct OutboundQueue {
+    /// Responses that may be waiting for one client before its queue counts
+    /// as full.
+    pub capacity: usize,
+    /// What to do once a client's queue is full.
+    pub policy: OverflowPolicy,
+    /// Seconds a queue may stay full before the client is disconnected.
+    /// Only used by the `disconnect` policy.
+    pub disconnect_after: u64,
+}
+
+impl Default for OutboundQueue {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            policy: OverflowPolicy::DropOldest,
+            disconnect_after: 10,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued push to make room for a new one. Replies are
+    /// never dropped.
+    DropOldest,
+    /// Keep queueing, but disconnect the client if its queue stays full
+    /// for `disconnect_after` seconds.
+    Disconnect,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -21,6 +132,23 @@ pub struct Database {
     pub path: String,
 }
 
+/// Storage of sent messages, so members that were offline get them later.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(default)]
+pub struct Messages {
+    /// Seconds a message is kept for. Zero disables storing messages.
+    /// Channels may choose a shorter retention, but not a longer one.
+    pub retention: u64,
+}
+
+impl Default for Messages {
+    fn default() -> Self {
+        Self {
+            retention: 7 * 24 * 60 * 60,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Influx {
     pub url: Url,
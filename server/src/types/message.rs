@@ -0,0 +1,498 @@
+//! Database storage for channel messages, so members that are offline when
+//! a message is sent still get it, and so members can scroll back.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, TimeZone, Utc};
+use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+use sha3::{Digest, Sha3_256};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::State;
+use crate::types::protocol::{HistoryCursor, HistoryMessage, MessageResponse, Reaction};
+
+/// What the server gives a message when it receives it.
+#[derive(Debug, Clone, Copy)]
+pub struct Stamp {
+    pub id: i64,
+    pub sequence: i64,
+    pub received: NaiveDateTime,
+}
+
+/// Gives a message the next sequence number in its channel and stores it.
+/// Messages are stored even if the channel keeps no history, so every
+/// message has an id. Retention cleanup removes them later.
+pub async fn record(state: &RwLock<State>, channel: Uuid, sender: u64, message: &[u8], reply_to: Option<u64>) -> Result<Stamp> {
+    let channel_id = channel.as_simple().to_string();
+    let sender = sender as i64;
+    let reply_to = reply_to.map(|id| id as i64);
+    // current_timestamp is only precise to the second
+    let received = Utc::now().naive_utc();
+
+    // in one transaction so ids and sequence numbers are in the same order
+    let mut tx = state.read().await.db.begin().await.context("could not start transaction")?;
+
+    let sequence = sqlx::query!(
+        // language=sqlite
+        "update channels set last_sequence = last_sequence + 1 where id = ? returning last_sequence as \"last_sequence!\"",
+        channel_id,
+    )
+        .fetch_one(&mut *tx)
+        .await
+        .context("could not get next sequence number")?
+        .last_sequence;
+
+    let id = sqlx::query!(
+        // language=sqlite
+        "insert into messages (channel_id, sender, message, created_at, sequence, reply_to) values (?, ?, ?, ?, ?, ?) returning id",
+        channel_id,
+        sender,
+        message,
+        received,
+        sequence,
+        reply_to,
+    )
+        .fetch_one(&mut *tx)
+        .await
+        .context("could not store message")?
+        .id;
+
+    tx.commit().await.context("could not commit message")?;
+
+    Ok(Stamp {
+        id,
+        sequence,
+        received,
+    })
+}
+
+/// Milliseconds since the Unix epoch, as sent to clients.
+pub fn millis(at: NaiveDateTime) -> i64 {
+    Utc.from_utc_datetime(&at).timestamp_millis()
+}
+
+/// Where a stored message was sent and who by.
+pub struct Origin {
+    pub channel: Uuid,
+    pub sender: u64,
+}
+
+pub async fn origin(state: &RwLock<State>, id: u64) -> Result<Option<Origin>> {
+    let id = id as i64;
+    let message = sqlx::query!(
+        // language=sqlite
+        "select channel_id, sender from messages where id = ?",
+        id,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not get message")?;
+
+    let message = match message {
+        Some(message) => message,
+        None => return Ok(None),
+    };
+
+    let channel = Uuid::from_str(&message.channel_id).context("invalid channel id")?;
+    Ok(Some(Origin {
+        channel,
+        sender: message.sender as u64,
+    }))
+}
+
+/// Replaces a stored message's content, returning when it was edited.
+pub async fn edit(state: &RwLock<State>, id: u64, message: &[u8]) -> Result<NaiveDateTime> {
+    let id = id as i64;
+    let edited = Utc::now().naive_utc();
+    sqlx::query!(
+        // language=sqlite
+        "update messages set message = ?, edited_at = ? where id = ?",
+        message,
+        edited,
+        id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not edit message")?;
+
+    Ok(edited)
+}
+
+/// Deletes a stored message, including from anyone's pending messages.
+pub async fn delete(state: &RwLock<State>, id: u64) -> Result<()> {
+    let id = id as i64;
+    sqlx::query!(
+        // language=sqlite
+        "delete from messages where id = ?",
+        id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not delete message")?;
+
+    Ok(())
+}
+
+/// Holds a stored message for a member until they next log in.
+pub async fn queue_for(state: &RwLock<State>, message_id: i64, lodestone_id: u64) -> Result<()> {
+    let lodestone_id = lodestone_id as i64;
+    sqlx::query!(
+        // language=sqlite
+        "insert or ignore into pending_messages (lodestone_id, message_id) values (?, ?)",
+        lodestone_id,
+        message_id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not queue message")?;
+
+    Ok(())
+}
+
+/// The messages held for a member, oldest first. They stay held until
+/// [`remove_pending`] is called with their ids, so a connection lost before
+/// they're written doesn't lose them. Messages from channels the member has
+/// since left are discarded.
+pub async fn get_pending(state: &RwLock<State>, lodestone_id: u64) -> Result<Vec<MessageResponse>> {
+    let lodestone_id = lodestone_id as i64;
+
+    sqlx::query!(
+        // language=sqlite
+        "
+        delete from pending_messages
+        where lodestone_id = ?
+          and message_id in (
+            select messages.id
+            from messages
+            where messages.channel_id not in (select channel_id from user_channels where lodestone_id = ?)
+          )
+        ",
+        lodestone_id,
+        lodestone_id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not discard pending messages from left channels")?;
+
+    let pending = sqlx::query!(
+        // language=sqlite
+        "
+        select messages.id, messages.channel_id, messages.message, messages.created_at, messages.sequence, messages.reply_to, users.name, users.world
+        from pending_messages
+            inner join messages on messages.id = pending_messages.message_id
+            inner join users on users.lodestone_id = messages.sender
+        where pending_messages.lodestone_id = ?
+        order by messages.id
+        ",
+        lodestone_id,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get pending messages")?;
+
+    let mut messages = Vec::with_capacity(pending.len());
+    for message in pending {
+        let channel = match Uuid::from_str(&message.channel_id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        messages.push(MessageResponse {
+            channel,
+            sender: message.name,
+            world: World::from_str(&message.world).map(crate::util::id_from_world).unwrap_or(0),
+            message: message.message.into(),
+            id: message.id as u64,
+            timestamp: millis(message.created_at),
+            sequence: message.sequence as u64,
+            reply_to: message.reply_to.map(|id| id as u64),
+        });
+    }
+
+    Ok(messages)
+}
+
+/// Stops holding messages for a member once they've been delivered.
+pub async fn remove_pending(state: &RwLock<State>, lodestone_id: u64, ids: &[u64]) -> Result<()> {
+    let lodestone_id = lodestone_id as i64;
+
+    let mut tx = state.read().await.db.begin().await.context("could not start transaction")?;
+    for &id in ids {
+        let id = id as i64;
+        sqlx::query!(
+            // language=sqlite
+            "delete from pending_messages where lodestone_id = ? and message_id = ?",
+            lodestone_id,
+            id,
+        )
+            .execute(&mut *tx)
+            .await
+            .context("could not remove pending message")?;
+    }
+    tx.commit().await.context("could not commit removed pending messages")?;
+
+    Ok(())
+}
+
+struct RawMessage {
+    id: i64,
+    name: String,
+    world: String,
+    message: Vec<u8>,
+    created_at: NaiveDateTime,
+    sequence: i64,
+    edited_at: Option<NaiveDateTime>,
+    reply_to: Option<i64>,
+}
+
+/// Loads up to `limit` messages from a channel on the given side of
+/// `cursor`, none older than `oldest`. Returns them oldest first, along
+/// with whether there were more. Reactions are marked as `viewer`'s or not.
+pub async fn page(state: &RwLock<State>, channel: Uuid, cursor: HistoryCursor, limit: u32, oldest: NaiveDateTime, viewer: u64) -> Result<(Vec<HistoryMessage>, bool)> {
+    let channel_id = channel.as_simple().to_string();
+    // one extra to see if there are more
+    let fetch = i64::from(limit) + 1;
+
+    let (raw, more) = match cursor {
+        HistoryCursor::Latest | HistoryCursor::Before(_) => {
+            let before = match cursor {
+                HistoryCursor::Before(id) => id.min(i64::MAX as u64) as i64,
+                _ => i64::MAX,
+            };
+
+            let mut raw = sqlx::query_as!(
+                RawMessage,
+                // language=sqlite
+                "
+                select messages.id, users.name, users.world, messages.message, messages.created_at, messages.sequence, messages.edited_at, messages.reply_to
+                from messages
+                    inner join users on users.lodestone_id = messages.sender
+                where messages.channel_id = ? and messages.id < ? and messages.created_at >= ?
+                order by messages.id desc
+                limit ?
+                ",
+                channel_id,
+                before,
+                oldest,
+                fetch,
+            )
+                .fetch_all(&state.read().await.db)
+                .await
+                .context("could not get history")?;
+
+            let more = raw.len() as i64 == fetch;
+            raw.truncate(limit as usize);
+            raw.reverse();
+            (raw, more)
+        }
+        HistoryCursor::After(id) => {
+            let after = id.min(i64::MAX as u64) as i64;
+            let mut raw = sqlx::query_as!(
+                RawMessage,
+                // language=sqlite
+                "
+                select messages.id, users.name, users.world, messages.message, messages.created_at, messages.sequence, messages.edited_at, messages.reply_to
+                from messages
+                    inner join users on users.lodestone_id = messages.sender
+                where messages.channel_id = ? and messages.id > ? and messages.created_at >= ?
+                order by messages.id
+                limit ?
+                ",
+                channel_id,
+                after,
+                oldest,
+                fetch,
+            )
+                .fetch_all(&state.read().await.db)
+                .await
+                .context("could not get history")?;
+
+            let more = raw.len() as i64 == fetch;
+            raw.truncate(limit as usize);
+            (raw, more)
+        }
+    };
+
+    let mut reactions = match (raw.first(), raw.last()) {
+        (Some(first), Some(last)) => self::reactions(state, channel, first.id, last.id, viewer).await?,
+        _ => Default::default(),
+    };
+
+    let messages = raw
+        .into_iter()
+        .map(|message| HistoryMessage {
+            id: message.id as u64,
+            sender: message.name,
+            world: World::from_str(&message.world).map(crate::util::id_from_world).unwrap_or(0),
+            timestamp: millis(message.created_at),
+            message: message.message.into(),
+            sequence: message.sequence as u64,
+            edited: message.edited_at.map(millis),
+            reply_to: message.reply_to.map(|id| id as u64),
+            reactions: reactions.remove(&message.id).unwrap_or_default(),
+        })
+        .collect();
+
+    Ok((messages, more))
+}
+
+/// The reactions on a channel's messages with ids from `first` to `last`,
+/// counted up per message.
+async fn reactions(state: &RwLock<State>, channel: Uuid, first: i64, last: i64, viewer: u64) -> Result<HashMap<i64, Vec<Reaction>>> {
+    let channel_id = channel.as_simple().to_string();
+    let viewer = viewer as i64;
+    let counted = sqlx::query!(
+        // language=sqlite
+        r#"
+        select
+            message_reactions.message_id,
+            min(message_reactions.reaction) as "reaction!: Vec<u8>",
+            count(*) as "count!: i64",
+            max(message_reactions.lodestone_id = ?) as "mine!: bool"
+        from message_reactions
+            inner join messages on messages.id = message_reactions.message_id
+        where messages.channel_id = ? and message_reactions.message_id between ? and ?
+        group by message_reactions.message_id, message_reactions.hash
+        order by min(message_reactions.rowid)
+        "#,
+        viewer,
+        channel_id,
+        first,
+        last,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get reactions")?;
+
+    let mut reactions: HashMap<i64, Vec<Reaction>> = HashMap::new();
+    for row in counted {
+        reactions.entry(row.message_id).or_default().push(Reaction {
+            reaction: row.reaction.into(),
+            count: row.count as u32,
+            mine: row.mine,
+        });
+    }
+
+    Ok(reactions)
+}
+
+/// Adds a member's reaction to a message, returning whether they hadn't
+/// already reacted with it.
+pub async fn add_reaction(state: &RwLock<State>, id: u64, lodestone_id: u64, reaction: &[u8]) -> Result<bool> {
+    let id = id as i64;
+    let lodestone_id = lodestone_id as i64;
+    let hash = reaction_hash(reaction);
+    let result = sqlx::query!(
+        // language=sqlite
+        "insert or ignore into message_reactions (message_id, lodestone_id, hash, reaction) values (?, ?, ?, ?)",
+        id,
+        lodestone_id,
+        hash,
+        reaction,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not add reaction")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+/// Removes a member's reaction from a message, returning whether they had
+/// reacted with it.
+pub async fn remove_reaction(state: &RwLock<State>, id: u64, lodestone_id: u64, reaction: &[u8]) -> Result<bool> {
+    let id = id as i64;
+    let lodestone_id = lodestone_id as i64;
+    let hash = reaction_hash(reaction);
+    let result = sqlx::query!(
+        // language=sqlite
+        "delete from message_reactions where message_id = ? and lodestone_id = ? and hash = ?",
+        id,
+        lodestone_id,
+        hash,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not remove reaction")?;
+
+    Ok(result.rows_affected() > 0)
+}
+
+fn reaction_hash(reaction: &[u8]) -> String {
+    let mut hasher = Sha3_256::new();
+    hasher.update(reaction);
+    hex::encode(&hasher.finalize()[..])
+}
+
+/// How many seconds a channel's messages are kept for: its own retention,
+/// capped at the server's.
+pub async fn retention(state: &RwLock<State>, channel: Uuid) -> Result<u64> {
+    let server = state.read().await.config.messages.retention;
+
+    let channel_id = channel.as_simple().to_string();
+    let channel = sqlx::query!(
+        // language=sqlite
+        "select retention from channels where id = ?",
+        channel_id,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not get channel retention")?;
+
+    Ok(match channel.and_then(|channel| channel.retention) {
+        Some(retention) => (retention.max(0) as u64).min(server),
+        None => server,
+    })
+}
+
+/// The oldest a message can be while still within `retention` seconds.
+pub fn cutoff(retention: u64) -> NaiveDateTime {
+    // chrono panics on durations this far out, and anything longer than a
+    // thousand years may as well be forever
+    let retention = retention.min(1000 * 365 * 24 * 60 * 60);
+    Utc::now().naive_utc() - chrono::Duration::seconds(retention as i64)
+}
+
+/// Deletes messages older than the server's retention, or their channel's
+/// if that's shorter, returning how many were removed.
+pub async fn remove_expired(state: &RwLock<State>) -> Result<u64> {
+    let retention = state.read().await.config.messages.retention;
+    let oldest = cutoff(retention);
+    let mut removed = sqlx::query!(
+        // language=sqlite
+        "delete from messages where created_at < ?",
+        oldest,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not remove expired messages")?
+        .rows_affected();
+
+    let retention = retention.min(i64::MAX as u64) as i64;
+    let channels = sqlx::query!(
+        // language=sqlite
+        "select id, retention as \"retention!\" from channels where retention is not null and retention < ?",
+        retention,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get channel retentions")?;
+
+    for channel in channels {
+        let oldest = cutoff(channel.retention.max(0) as u64);
+        removed += sqlx::query!(
+            // language=sqlite
+            "delete from messages where channel_id = ? and created_at < ?",
+            channel.id,
+            oldest,
+        )
+            .execute(&state.read().await.db)
+            .await
+            .context("could not remove expired messages")?
+            .rows_affected();
+    }
+
+    Ok(removed)
+}
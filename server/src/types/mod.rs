@@ -1,3 +1,8 @@
-pub mod protocol;
+pub use extrachat_protocol as protocol;
+
+pub mod ban;
+pub mod channel;
+pub mod message;
+pub mod mute;
 pub mod user;
 pub mod config;
@@ -0,0 +1,118 @@
+//! Database access for timed mutes on channel members.
+
+use std::collections::HashMap;
+use std::str::FromStr;
+
+use anyhow::{Context, Result};
+use chrono::{NaiveDateTime, Utc};
+use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::State;
+
+/// When each muted member of the channel is unmuted, in milliseconds since
+/// the Unix epoch, by lodestone id. Mutes that have run out are left out
+/// even before they're cleaned up.
+pub async fn get_all(state: &RwLock<State>, channel: Uuid) -> Result<HashMap<u64, i64>> {
+    let channel_id = channel.as_simple().to_string();
+    let now = Utc::now().naive_utc();
+    let mutes = sqlx::query!(
+        // language=sqlite
+        r#"select lodestone_id, muted_until as "muted_until!: NaiveDateTime" from user_channels where channel_id = ? and muted_until > ?"#,
+        channel_id,
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not get mutes")?;
+
+    Ok(mutes
+        .into_iter()
+        .map(|mute| (mute.lodestone_id as u64, crate::types::message::millis(mute.muted_until)))
+        .collect())
+}
+
+/// When the member's mute is lifted, if they're muted.
+pub async fn muted_until(state: &RwLock<State>, channel: Uuid, lodestone_id: u64) -> Result<Option<NaiveDateTime>> {
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    let now = Utc::now().naive_utc();
+    let mute = sqlx::query!(
+        // language=sqlite
+        r#"select muted_until as "muted_until!: NaiveDateTime" from user_channels where channel_id = ? and lodestone_id = ? and muted_until > ?"#,
+        channel_id,
+        lodestone_id,
+        now,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not get mute")?;
+
+    Ok(mute.map(|mute| mute.muted_until))
+}
+
+/// Mutes the member until `until`, or unmutes them if `None`.
+pub async fn set(state: &RwLock<State>, channel: Uuid, lodestone_id: u64, until: Option<NaiveDateTime>) -> Result<()> {
+    let channel_id = channel.as_simple().to_string();
+    let lodestone_id = lodestone_id as i64;
+    sqlx::query!(
+        // language=sqlite
+        "update user_channels set muted_until = ? where channel_id = ? and lodestone_id = ?",
+        until,
+        channel_id,
+        lodestone_id,
+    )
+        .execute(&state.read().await.db)
+        .await
+        .context("could not set mute")?;
+
+    Ok(())
+}
+
+/// A mute that was cleared because it ran out.
+pub struct ExpiredMute {
+    pub channel: Uuid,
+    pub name: String,
+    pub world: u16,
+}
+
+/// Clears mutes that have run out.
+pub async fn remove_expired(state: &RwLock<State>) -> Result<Vec<ExpiredMute>> {
+    let now = Utc::now().naive_utc();
+    let cleared = sqlx::query!(
+        // language=sqlite
+        "update user_channels set muted_until = null where muted_until <= ? returning channel_id, lodestone_id",
+        now,
+    )
+        .fetch_all(&state.read().await.db)
+        .await
+        .context("could not clear expired mutes")?;
+
+    let mut expired = Vec::with_capacity(cleared.len());
+    for mute in cleared {
+        let channel = match Uuid::from_str(&mute.channel_id) {
+            Ok(u) => u,
+            Err(_) => continue,
+        };
+
+        let user = sqlx::query!(
+            // language=sqlite
+            "select name, world from users where lodestone_id = ?",
+            mute.lodestone_id,
+        )
+            .fetch_optional(&state.read().await.db)
+            .await
+            .context("could not get muted user")?;
+
+        if let Some(user) = user {
+            expired.push(ExpiredMute {
+                channel,
+                name: user.name,
+                world: World::from_str(&user.world).map(crate::util::id_from_world).unwrap_or(0),
+            });
+        }
+    }
+
+    Ok(expired)
+}
@@ -1,4 +1,8 @@
+use anyhow::{Context, Result};
 use lodestone_scraper::lodestone_parser::ffxiv_types::World;
+use tokio::sync::RwLock;
+
+use crate::State;
 
 #[derive(Debug, Clone)]
 pub struct User {
@@ -7,3 +11,31 @@ pub struct User {
     pub world: World,
     pub hash: String,
 }
+
+/// A registered character found by name, whether they're online or not.
+pub struct Registered {
+    pub lodestone_id: u64,
+    pub invite_pk: Option<Vec<u8>>,
+}
+
+pub async fn find(state: &RwLock<State>, name: &str, world: u16) -> Result<Option<Registered>> {
+    let world = match crate::util::world_from_id(world) {
+        Some(world) => world.as_str(),
+        None => return Ok(None),
+    };
+
+    let user = sqlx::query!(
+        // language=sqlite
+        "select lodestone_id, invite_pk from users where name = ? and world = ?",
+        name,
+        world,
+    )
+        .fetch_optional(&state.read().await.db)
+        .await
+        .context("could not look up user")?;
+
+    Ok(user.map(|user| Registered {
+        lodestone_id: user.lodestone_id as u64,
+        invite_pk: user.invite_pk,
+    }))
+}
@@ -4,22 +4,45 @@ use prefixed_api_key::ApiKey;
 use sha3::Sha3_256;
 use tokio::sync::RwLock;
 use tokio_tungstenite::tungstenite::Message as WsMessage;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
 use uuid::Uuid;
 
-use crate::{Digest, ResponseContainer, State, types::protocol::ResponseKind, World, WsStream};
+use crate::{Digest, Outbound, ResponseContainer, State, types::protocol::ResponseKind, World, WsStream};
+use crate::types::protocol::codec::{self, Encoding};
 
-pub mod redacted;
-
-pub async fn send(conn: &mut WsStream, number: u32, msg: impl Into<ResponseKind>) -> Result<()> {
-    let container = ResponseContainer {
+/// Queues a reply for the client.
+pub async fn send(out: &Outbound, number: u32, msg: impl Into<ResponseKind>) -> Result<()> {
+    out.send(ResponseContainer {
         number,
         kind: msg.into(),
+    })?;
+    Ok(())
+}
+
+/// Writes a response straight to the socket, in the encoding the client
+/// last used.
+pub async fn send_container(conn: &mut WsStream, container: &ResponseContainer) -> Result<()> {
+    let frame = match conn.encoding {
+        Encoding::MessagePack => WsMessage::Binary(codec::encode_response(container)?),
+        Encoding::Json => WsMessage::Text(codec::encode_response_json(container)?),
     };
 
-    conn.send(WsMessage::Binary(rmp_serde::to_vec(&container)?)).await?;
+    conn.ws.send(frame).await?;
     Ok(())
 }
 
+/// Sends a close frame, giving up after a few seconds since the other end
+/// may no longer be reading.
+pub async fn close(conn: &mut WsStream, code: CloseCode, reason: &'static str) {
+    let frame = CloseFrame {
+        code,
+        reason: reason.into(),
+    };
+
+    tokio::time::timeout(std::time::Duration::from_secs(5), conn.ws.close(Some(frame))).await.ok();
+}
+
 pub async fn send_to_all(state: &RwLock<State>, channel_id: Uuid, number: u32, msg: impl Into<ResponseKind>) -> Result<()> {
     let members = get_raw_members(state, channel_id).await?
         .into_iter()
@@ -31,7 +54,24 @@ pub async fn send_to_all(state: &RwLock<State>, channel_id: Uuid, number: u32, m
     };
     for member in members {
         if let Some(client) = state.read().await.clients.get(&(member.lodestone_id as u64)) {
-            client.read().await.tx.send(resp.clone()).await.ok();
+            client.read().await.tx.push(resp.clone());
+        }
+    }
+
+    Ok(())
+}
+
+/// Like [`send_to_all`], but leaves out anyone who's only invited.
+pub async fn send_to_members(state: &RwLock<State>, channel_id: Uuid, number: u32, msg: impl Into<ResponseKind>) -> Result<()> {
+    let members = get_raw_members(state, channel_id).await?;
+
+    let resp = ResponseContainer {
+        number,
+        kind: msg.into(),
+    };
+    for member in members {
+        if let Some(client) = state.read().await.clients.get(&(member.lodestone_id as u64)) {
+            client.read().await.tx.push(resp.clone());
         }
     }
 